@@ -0,0 +1,522 @@
+use std::collections::BTreeMap;
+
+use data::{
+    anomalies::{Anomaly, AnomalyBehavior},
+    bosses::{get_boss_by_type, BossDefinition},
+    effects::{StatusEffect, StatusEffectType},
+    elements::{Effectiveness, Element, HOME_REGION_DAMAGE_BONUS},
+    items::{get_item_by_type, Item, ItemKind, ItemType},
+    passives::PassiveEffect,
+    skills::{get_skill_by_type, Skill, SkillType},
+    Emoji, Probability, Stat,
+};
+use rand::{seq::SliceRandom, Rng};
+
+use super::{ActionType, Battle};
+use format as f;
+use util::math;
+
+const DEFAULT_IMAGE: &str = "https://i.imgur.com/Kl2qRLF.png";
+
+const DEFEND_DAMAGE_REDUCTION: f32 = 0.5;
+
+pub const PLAYER_TEAM: usize = 0;
+pub const ANOMALY_TEAM: usize = 1;
+
+/// Everything a player brings into a battle, with the bonuses of equipment and talents applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub health: Stat,
+    pub mana: Stat,
+    pub strength: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+    pub skills: Vec<SkillType>,
+    pub passives: Vec<PassiveEffect>,
+    // Only the consumables are carried into the battle
+    pub items: BTreeMap<ItemType, i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fighter {
+    pub name: String,
+    pub user_id: Option<u64>,
+    // Picture shown by front-ends, anomalies use their own image
+    pub image: Option<String>,
+    pub anomaly: Option<Anomaly>,
+    // Number of boss phases already entered
+    pub boss_phase: usize,
+    pub team: usize,
+    pub target_index: Option<usize>,
+    pub health: Stat,
+    pub mana: Stat,
+    pub strength: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+    // Element of the fighter's basic attacks and of the damage it receives
+    pub element: Element,
+    pub skills: Vec<SkillType>,
+    pub passives: Vec<PassiveEffect>,
+    // Whether the fighter already used an attack or a damaging skill in this battle
+    pub has_struck: bool,
+    pub defending: bool,
+    pub effects: Vec<StatusEffect>,
+    // Consumables the fighter carries into the battle
+    pub items: BTreeMap<ItemType, i32>,
+    // Consumables spent during the battle, to be removed from the owner's inventory
+    pub used_items: Vec<ItemType>,
+}
+
+impl Fighter {
+    pub fn create_from_player(user_id: u64, name: impl Into<String>, stats: PlayerStats) -> Self {
+        let items = stats
+            .items
+            .into_iter()
+            .filter(|&(item, _)| {
+                get_item_by_type(item)
+                    .is_some_and(|i| matches!(i.kind, ItemKind::Consumable { .. }))
+            })
+            .collect();
+
+        Self {
+            name: name.into(),
+            user_id: Some(user_id),
+            image: None,
+            anomaly: None,
+            boss_phase: 0,
+            team: PLAYER_TEAM,
+            target_index: None,
+            health: stats.health,
+            mana: stats.mana,
+            strength: stats.strength.max(1),
+            agility: stats.agility.max(1),
+            intelligence: stats.intelligence.max(1),
+            element: Element::Neutral,
+            skills: stats.skills,
+            passives: stats.passives,
+            has_struck: false,
+            defending: false,
+            effects: Vec::new(),
+            items,
+            used_items: Vec::new(),
+        }
+    }
+
+    pub fn create_from_anomaly(anomaly: Anomaly) -> Self {
+        Self {
+            name: anomaly.name(),
+            user_id: None,
+            image: None,
+            anomaly: Some(anomaly),
+            boss_phase: 0,
+            team: ANOMALY_TEAM,
+            target_index: None,
+            health: anomaly.health,
+            mana: anomaly.mana,
+            strength: anomaly.strength,
+            agility: anomaly.agility,
+            intelligence: anomaly.intelligence,
+            element: anomaly.element(),
+            skills: anomaly.definition.skills.to_vec(),
+            passives: Vec::new(),
+            has_struck: false,
+            defending: false,
+            effects: Vec::new(),
+            items: BTreeMap::new(),
+            used_items: Vec::new(),
+        }
+    }
+
+    pub fn boss(&self) -> Option<BossDefinition> {
+        self.anomaly.and_then(|a| get_boss_by_type(a.anomaly_type))
+    }
+
+    // Enters the next boss phase once its health threshold is reached, returning its index
+    pub fn try_advance_boss_phase(&mut self) -> Option<usize> {
+        let phase = *self.boss()?.phases.get(self.boss_phase)?;
+
+        if self.health.value == 0 || self.health.percentage() > phase.health_threshold {
+            return None;
+        }
+
+        self.strength = (self.strength as f32 * phase.strength_multiplier) as i32;
+        self.agility = (self.agility as f32 * phase.agility_multiplier) as i32;
+        self.skills = phase.skills.to_vec();
+
+        if let Some(anomaly) = self.anomaly.as_mut() {
+            anomaly.definition.behavior = phase.behavior;
+        }
+
+        self.boss_phase += 1;
+        Some(self.boss_phase - 1)
+    }
+
+    fn calculate_damage_multiplier(&self, critical: bool, rng: &mut impl Rng) -> f32 {
+        let mut multiplier = rng.gen_range(0.8..=1.2f32);
+
+        if critical {
+            multiplier += rng.gen_range(0.75f32..1.2f32);
+
+            let bonus = self.passive_total(|p| match p {
+                PassiveEffect::CriticalDamage(percentage) => Some(percentage),
+                _ => None,
+            });
+            multiplier *= 1.0 + bonus as f32 / 100.0;
+        }
+
+        if self.anomaly.is_some_and(|a| a.at_home) {
+            multiplier *= 1.0 + HOME_REGION_DAMAGE_BONUS;
+        }
+
+        multiplier
+    }
+
+    pub fn calculate_damage(&self, critical: bool, rng: &mut impl Rng) -> i32 {
+        (self.strength as f32 * self.calculate_damage_multiplier(critical, rng)) as i32
+    }
+
+    pub fn calculate_skill_damage(
+        &self,
+        strength_factor: f32,
+        intelligence_factor: f32,
+        critical: bool,
+        rng: &mut impl Rng,
+    ) -> i32 {
+        let base =
+            self.strength as f32 * strength_factor + self.intelligence as f32 * intelligence_factor;
+
+        (base * self.calculate_damage_multiplier(critical, rng)) as i32
+    }
+
+    pub fn calculate_skill_heal(&self, base: i32, intelligence_factor: f32) -> i32 {
+        base + (self.intelligence as f32 * intelligence_factor) as i32
+    }
+
+    pub fn available_skills(&self) -> Vec<Skill> {
+        self.skills
+            .iter()
+            .copied()
+            .filter_map(get_skill_by_type)
+            .collect()
+    }
+
+    pub fn usable_items(&self) -> Vec<(Item, i32)> {
+        self.items
+            .iter()
+            .filter(|(_, &amount)| amount > 0)
+            .filter_map(|(&item, &amount)| Some((get_item_by_type(item)?, amount)))
+            .collect()
+    }
+
+    pub fn can_use_item(&self, item: ItemType) -> bool {
+        let consumable =
+            get_item_by_type(item).is_some_and(|i| matches!(i.kind, ItemKind::Consumable { .. }));
+
+        consumable && self.items.get(&item).is_some_and(|&amount| amount > 0)
+    }
+
+    pub fn consume_item(&mut self, item: ItemType) -> bool {
+        let Some(amount) = self.items.get_mut(&item).filter(|a| **a > 0) else {
+            return false;
+        };
+
+        *amount -= 1;
+        self.used_items.push(item);
+        true
+    }
+
+    pub fn can_afford(&self, skill: &Skill) -> bool {
+        self.mana.value >= skill.mana_cost
+    }
+
+    pub fn calculate_dodge_chance(&self, other: &Fighter) -> Probability {
+        let chance = math::calculate_dodge_chance(self.agility, other.agility);
+
+        match self.anomaly.and_then(|a| a.variant) {
+            Some(variant) => Probability::new(chance.value().saturating_add(variant.dodge_bonus())),
+            None => chance,
+        }
+    }
+
+    pub fn calculate_critical_chance(&self, other: &Fighter) -> Probability {
+        math::calculate_critical_chance(self.intelligence, other.intelligence)
+    }
+
+    pub fn calculate_flee_chance(&self, other: &Fighter) -> Probability {
+        math::calculate_flee_chance(self.agility, other.agility)
+    }
+
+    pub fn has_passive(&self, passive: PassiveEffect) -> bool {
+        self.passives.contains(&passive)
+    }
+
+    // Sum of the values of every passive matched by `value`
+    pub fn passive_total(&self, value: impl Fn(PassiveEffect) -> Option<i32>) -> i32 {
+        self.passives.iter().copied().filter_map(value).sum()
+    }
+
+    // How well an attack of the given element works against this fighter
+    pub fn effectiveness_of(&self, element: Element) -> Effectiveness {
+        element.effectiveness_against(self.element)
+    }
+
+    // Damage that actually reaches this fighter after defensive reductions
+    pub fn calculate_received_damage(&self, damage: i32) -> i32 {
+        let reduction = self
+            .passive_total(|p| match p {
+                PassiveEffect::DamageReduction(percentage) => Some(percentage),
+                _ => None,
+            })
+            .min(90);
+        let damage = (damage as f32 * (1.0 - reduction as f32 / 100.0)) as i32;
+
+        if self.defending {
+            (damage as f32 * (1.0 - DEFEND_DAMAGE_REDUCTION)) as i32
+        } else {
+            damage
+        }
+    }
+
+    pub fn take_damage(&mut self, damage: i32) {
+        self.health.subtract_value(damage);
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        self.health.add_value(amount);
+    }
+
+    pub fn remove_mana(&mut self, amount: i32) {
+        self.mana.subtract_value(amount);
+    }
+
+    pub fn restore_mana(&mut self, amount: i32) {
+        self.mana.add_value(amount);
+    }
+
+    pub fn remove_status_effect(&mut self, effect_type: StatusEffectType) -> bool {
+        let had_effect = self.has_status_effect(effect_type);
+        self.effects.retain(|e| e.effect_type != effect_type);
+
+        had_effect
+    }
+
+    pub fn has_status_effect(&self, effect_type: StatusEffectType) -> bool {
+        self.effects.iter().any(|e| e.effect_type == effect_type)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has_status_effect(StatusEffectType::Stun)
+    }
+
+    // Reapplying an effect refreshes it instead of stacking
+    pub fn add_status_effect(&mut self, effect: StatusEffect) {
+        if let Some(current) = self
+            .effects
+            .iter_mut()
+            .find(|e| e.effect_type == effect.effect_type)
+        {
+            current.turns = current.turns.max(effect.turns);
+            current.potency = current.potency.max(effect.potency);
+            return;
+        }
+
+        self.effects.push(effect);
+    }
+
+    pub fn display_skills(&self) -> String {
+        let skills = self.available_skills();
+        if skills.is_empty() {
+            return "Nenhuma".into();
+        }
+
+        skills
+            .iter()
+            .map(|s| {
+                f!(
+                    "{} **{}** (`{}` {}, {} {}): {}",
+                    s.emoji,
+                    s.name,
+                    s.mana_cost,
+                    Emoji::Mana,
+                    s.element.emoji(),
+                    s.element.name(),
+                    s.description
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn display_passives(&self) -> String {
+        self.passives
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    pub fn display_status_effects(&self) -> String {
+        self.effects
+            .iter()
+            .map(|e| {
+                f!(
+                    "{} {} (`{}`)",
+                    e.effect_type.emoji(),
+                    e.effect_type.name(),
+                    e.turns
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    pub fn image(&self) -> String {
+        if let Some(anomaly) = self.anomaly {
+            anomaly.image().into()
+        } else {
+            self.image.clone().unwrap_or(DEFAULT_IMAGE.into())
+        }
+    }
+
+    pub fn display_full_stats(&self) -> String {
+        let mut stats = format!(
+            "{} Vida: {} (`{}%`)\n{} Mana: {} (`{}%`)\n{} Força: {}\n{} Inteligência: `{}`\n{} Agilidade: `{}`",
+            Emoji::Health, self.health, self.health.percentage(),
+            Emoji::Mana, self.mana, self.mana.percentage(),
+            Emoji::Strength, self.strength,
+            Emoji::Intelligence, self.intelligence,
+            Emoji::Agility, self.agility
+        );
+
+        if self.element != Element::Neutral {
+            stats.push_str(&format!("\n{}", self.display_element()));
+        }
+
+        stats
+    }
+
+    pub fn display_element(&self) -> String {
+        let list = |elements: Vec<Element>| {
+            elements
+                .iter()
+                .map(|e| e.emoji())
+                .collect::<Vec<&str>>()
+                .join(" ")
+        };
+
+        let mut element = format!("Elemento: {} {}", self.element.emoji(), self.element.name());
+
+        let weaknesses = self.element.weaknesses();
+        if !weaknesses.is_empty() {
+            element.push_str(&format!(" • Fraco a: {}", list(weaknesses)));
+        }
+
+        let resistances = self.element.resistances();
+        if !resistances.is_empty() {
+            element.push_str(&format!(" • Resiste a: {}", list(resistances)));
+        }
+
+        if self.anomaly.is_some_and(|a| a.at_home) {
+            element.push_str(&format!(
+                "\n🏠 Em sua região natal: `+{}%` de dano",
+                (HOME_REGION_DAMAGE_BONUS * 100.0) as i32
+            ));
+        }
+
+        element
+    }
+
+    pub fn display_full_stats_with_target(&self, target: &Fighter) -> String {
+        let mut stats = format!(
+            "{} Vida: {} (`{}%`)\n{} Mana: {} (`{}%`)\n{} Força: {}\nChance de crítico: `{}`\nChance de esquiva: `{}`",
+            Emoji::Health, self.health, self.health.percentage(),
+            Emoji::Mana, self.mana, self.mana.percentage(),
+            Emoji::Strength, self.strength,
+            self.calculate_critical_chance(target),
+            self.calculate_dodge_chance(target)
+        );
+
+        if self.element != Element::Neutral {
+            stats.push_str(&format!("\n{}", self.display_element()));
+        }
+
+        if !self.passives.is_empty() {
+            stats.push_str(&format!("\nPassivas: {}", self.display_passives()));
+        }
+
+        if !self.effects.is_empty() {
+            stats.push_str(&format!("\nEfeitos: {}", self.display_status_effects()));
+        }
+
+        stats
+    }
+
+    // Aggressive anomalies focus the weakest opponent, the others pick at random
+    pub fn choose_target(&self, battle: &Battle, rng: &mut impl Rng) -> Option<usize> {
+        let anomaly = self.anomaly?;
+        let opponents = battle.opponents_of(battle.current_fighter_index());
+
+        match anomaly.definition.behavior {
+            AnomalyBehavior::Aggressive => opponents
+                .iter()
+                .copied()
+                .min_by_key(|&i| battle.fighters()[i].health.value),
+            _ => opponents.choose(rng).copied(),
+        }
+    }
+
+    pub fn choose_action_type(&self, battle: &Battle, rng: &mut impl Rng) -> ActionType {
+        let Some(anomaly) = self.anomaly else {
+            return ActionType::Attack;
+        };
+
+        let target = battle.target_fighter();
+
+        let affordable_skills: Vec<Skill> = self
+            .available_skills()
+            .into_iter()
+            .filter(|s| self.can_afford(s))
+            .collect();
+
+        let healing_skill = affordable_skills.iter().find(|s| s.heals());
+        let damage_skill = affordable_skills
+            .iter()
+            .filter(|s| s.deals_damage())
+            .max_by_key(|s| s.mana_cost);
+
+        match anomaly.definition.behavior {
+            AnomalyBehavior::Healer { health_threshold }
+                if self.health.percentage() < health_threshold =>
+            {
+                if let Some(skill) = healing_skill {
+                    return ActionType::Skill(skill.skill_type);
+                }
+            }
+            AnomalyBehavior::Defensive { health_threshold }
+                if self.health.percentage() < health_threshold
+                    && !self.defending
+                    && rng.gen_bool(0.6) =>
+            {
+                return ActionType::Defend;
+            }
+            _ => {}
+        }
+
+        let Some(skill) = damage_skill else {
+            return ActionType::Attack;
+        };
+
+        // Skills are wasted on a defending target unless the anomaly is aggressive
+        let skill_chance = match anomaly.definition.behavior {
+            AnomalyBehavior::Aggressive => 0.8,
+            _ if target.defending => 0.1,
+            _ => 0.35,
+        };
+
+        if rng.gen_bool(skill_chance) {
+            ActionType::Skill(skill.skill_type)
+        } else {
+            ActionType::Attack
+        }
+    }
+}
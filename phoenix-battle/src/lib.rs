@@ -0,0 +1,1135 @@
+mod fighter;
+
+use std::{cmp::Reverse, error::Error};
+
+use data::{
+    effects::{EffectTiming, StatusEffect, StatusEffectType, StatusInfliction},
+    elements::Effectiveness,
+    items::{get_item_by_type, ItemEffect, ItemKind, ItemType},
+    passives::PassiveEffect,
+    skills::{get_skill_by_name, get_skill_by_type, Skill, SkillEffect, SkillType},
+    Probability,
+};
+pub use fighter::{Fighter, PlayerStats, ANOMALY_TEAM, PLAYER_TEAM};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use util::math;
+
+pub type DynamicError = Box<dyn Error + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionType {
+    Attack,
+    Defend,
+    Flee,
+    Skill(SkillType),
+    UseItem(ItemType),
+}
+
+pub const ALL_ACTION_TYPES: [ActionType; 3] =
+    [ActionType::Attack, ActionType::Defend, ActionType::Flee];
+
+impl ActionType {
+    pub fn from_name(name: &str) -> Option<ActionType> {
+        ALL_ACTION_TYPES
+            .iter()
+            .copied()
+            .find(|a| a.name() == name)
+            .or_else(|| get_skill_by_name(name).map(|s| ActionType::Skill(s.skill_type)))
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            ActionType::Attack => "👊",
+            ActionType::Defend => "🛡️",
+            ActionType::Flee => "🏃",
+            ActionType::Skill(skill) => get_skill_by_type(*skill).map_or("✨", |s| s.emoji),
+            ActionType::UseItem(item) => get_item_by_type(*item).map_or("🎒", |i| i.emoji),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActionType::Attack => "Atacar",
+            ActionType::Defend => "Defender",
+            ActionType::Flee => "Fugir",
+            ActionType::Skill(skill) => get_skill_by_type(*skill).map_or("?", |s| s.name),
+            ActionType::UseItem(item) => get_item_by_type(*item).map_or("?", |i| i.name),
+        }
+    }
+}
+
+/// Something that happened during a round. Fighters are referenced by their index in the battle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BattleEvent {
+    Attack {
+        attacker: usize,
+        target: usize,
+        damage: i32,
+        critical: bool,
+    },
+    SkillUsed {
+        fighter: usize,
+        skill: SkillType,
+    },
+    NotEnoughMana {
+        fighter: usize,
+        skill: SkillType,
+    },
+    Dodge {
+        fighter: usize,
+    },
+    Damage {
+        fighter: usize,
+        amount: i32,
+        critical: bool,
+    },
+    Heal {
+        fighter: usize,
+        amount: i32,
+    },
+    ManaDrain {
+        fighter: usize,
+        amount: i32,
+    },
+    ManaRestored {
+        fighter: usize,
+        amount: i32,
+    },
+    ItemUsed {
+        fighter: usize,
+        item: ItemType,
+    },
+    StatusInflicted {
+        fighter: usize,
+        effect: StatusEffect,
+    },
+    StatusTick {
+        fighter: usize,
+        effect_type: StatusEffectType,
+        amount: i32,
+    },
+    StatusExpired {
+        fighter: usize,
+        effect_type: StatusEffectType,
+    },
+    Defend {
+        fighter: usize,
+    },
+    Fled {
+        fighter: usize,
+    },
+    FleeFailed {
+        fighter: usize,
+        chance: Probability,
+    },
+    BossPhase {
+        fighter: usize,
+        phase: usize,
+    },
+    /// The element of the damage received by the fighter was not neutral to its own
+    Effectiveness {
+        fighter: usize,
+        effectiveness: Effectiveness,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub fighter: usize,
+    // Target chosen by the fighter when the round started, needed to replay it
+    pub target: Option<usize>,
+    // None when the fighter lost its turn
+    pub action: Option<ActionType>,
+    pub events: Vec<BattleEvent>,
+}
+
+impl Round {
+    fn new(fighter: usize, target: Option<usize>, action: Option<ActionType>) -> Self {
+        Self {
+            fighter,
+            target,
+            action,
+            events: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BattleResult {
+    pub winning_team: Option<usize>,
+    pub winners: Vec<Fighter>,
+    pub survivors: Vec<Fighter>,
+    pub fled_fighter: Option<Fighter>,
+    pub defeated_fighters: Vec<Fighter>,
+    pub all_fighters: Vec<Fighter>,
+    pub battle: Battle,
+}
+
+#[derive(Debug, Clone)]
+pub struct Battle {
+    // Fighters as they were before the first round, needed to replay the battle
+    initial_fighters: Vec<Fighter>,
+    fighters: Vec<Fighter>,
+    current_fighter: usize,
+    // Fighter indexes ordered by initiative for the current turn cycle
+    turn_order: Vec<usize>,
+    turn: usize,
+    winning_team: Option<usize>,
+    fled_fighter: Option<usize>,
+    rounds: Vec<Round>,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Battle {
+    /// Every random outcome of the battle comes from `seed`, so the same seed and actions always
+    /// produce the same battle.
+    pub fn new(fighters: Vec<Fighter>, seed: u64) -> Result<Self, DynamicError> {
+        let mut battle = Self {
+            current_fighter: 0,
+            initial_fighters: fighters.clone(),
+            fighters,
+            turn_order: Vec::new(),
+            turn: 0,
+            winning_team: None,
+            fled_fighter: None,
+            rounds: Vec::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        };
+
+        if battle.alive_teams().len() < 2 {
+            Err("Battle needs fighters from two or more teams")?;
+        }
+
+        for i in 0..battle.fighters.len() {
+            battle.fighters[i].target_index = battle.opponents_of(i).first().copied();
+        }
+
+        battle.turn_order = battle.generate_turn_order();
+        battle.current_fighter = battle.turn_order[0];
+
+        Ok(battle)
+    }
+
+    /// Runs the recorded rounds again over the fighters as they were when the battle started.
+    pub fn replay(
+        fighters: Vec<Fighter>,
+        seed: u64,
+        rounds: &[Round],
+    ) -> Result<Self, DynamicError> {
+        let mut battle = Self::new(fighters, seed)?;
+
+        for round in rounds {
+            if battle.finished() {
+                break;
+            }
+
+            // Mirrors how front-ends drive a battle, so the RNG is consumed in the same order
+            if battle.current_fighter().is_stunned() {
+                battle.skip_turn();
+            } else if battle.current_fighter().user_id.is_none() {
+                let action = battle.choose_ai_action();
+                battle.run_action(action)?;
+            } else {
+                if let Some(target) = round.target {
+                    battle.set_target(target)?;
+                }
+
+                match round.action {
+                    Some(action) => battle.run_action(action)?,
+                    None => battle.skip_turn(),
+                };
+            }
+        }
+
+        Ok(battle)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn fighters(&self) -> &Vec<Fighter> {
+        &self.fighters
+    }
+
+    pub fn initial_fighters(&self) -> &Vec<Fighter> {
+        &self.initial_fighters
+    }
+
+    fn alive_teams(&self) -> Vec<usize> {
+        let mut teams: Vec<usize> = self
+            .fighters
+            .iter()
+            .filter(|f| f.health.value > 0)
+            .map(|f| f.team)
+            .collect();
+
+        teams.sort();
+        teams.dedup();
+        teams
+    }
+
+    // Indexes of the alive fighters that are not on the given fighter's team
+    pub fn opponents_of(&self, fighter_index: usize) -> Vec<usize> {
+        let team = self.fighters[fighter_index].team;
+
+        (0..self.fighters.len())
+            .filter(|&i| self.fighters[i].team != team && self.fighters[i].health.value > 0)
+            .collect()
+    }
+
+    pub fn current_fighter_index(&self) -> usize {
+        self.current_fighter
+    }
+
+    pub fn set_target(&mut self, target_index: usize) -> Result<(), DynamicError> {
+        if !self
+            .opponents_of(self.current_fighter)
+            .contains(&target_index)
+        {
+            Err("Invalid target")?;
+        }
+
+        self.current_fighter_mut().target_index = Some(target_index);
+        Ok(())
+    }
+
+    // Keeps the current fighter from hitting someone that has already fallen
+    fn ensure_valid_target(&mut self) {
+        let opponents = self.opponents_of(self.current_fighter);
+        let target_index = self.current_fighter().target_index;
+
+        if target_index.is_none_or(|i| !opponents.contains(&i)) {
+            self.current_fighter_mut().target_index = opponents.first().copied();
+        }
+    }
+
+    // Faster fighters act first, ties are broken randomly
+    fn generate_turn_order(&mut self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.fighters.len())
+            .filter(|&i| self.fighters[i].health.value > 0)
+            .collect();
+
+        order.shuffle(&mut self.rng);
+        order.sort_by_key(|&i| Reverse(self.fighters[i].agility));
+
+        let mut extra_actions = Vec::new();
+        for &i in &order {
+            let slowest_opponent = order
+                .iter()
+                .filter(|&&j| self.fighters[j].team != self.fighters[i].team)
+                .map(|&j| self.fighters[j].agility)
+                .min()
+                .unwrap_or(i32::MAX);
+
+            if math::calculate_extra_action_chance(self.fighters[i].agility, slowest_opponent)
+                .generate_random_bool(&mut self.rng)
+            {
+                extra_actions.push(i);
+            }
+        }
+
+        order.extend(extra_actions);
+        order
+    }
+
+    pub fn turn_order(&self) -> Vec<&Fighter> {
+        self.turn_order
+            .iter()
+            .skip(self.turn)
+            .map(|&i| &self.fighters[i])
+            .collect()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.winning_team.is_some() || self.fled_fighter.is_some()
+    }
+
+    pub fn winning_team(&self) -> Option<usize> {
+        self.winning_team
+    }
+
+    pub fn winners(&self) -> Vec<Fighter> {
+        self.fighters
+            .iter()
+            .filter(|f| Some(f.team) == self.winning_team)
+            .cloned()
+            .collect()
+    }
+
+    // Winners that were still standing when the battle ended
+    pub fn survivors(&self) -> Vec<Fighter> {
+        self.winners()
+            .into_iter()
+            .filter(|f| f.health.value > 0)
+            .collect()
+    }
+
+    pub fn fled_fighter(&self) -> Option<&Fighter> {
+        self.fled_fighter.map(|i| &self.fighters[i])
+    }
+
+    pub fn rounds(&self) -> &Vec<Round> {
+        &self.rounds
+    }
+
+    pub fn result(&self) -> BattleResult {
+        BattleResult {
+            battle: self.clone(),
+            all_fighters: self.fighters.clone(),
+            defeated_fighters: self
+                .fighters
+                .iter()
+                .filter(|f| f.health.value == 0)
+                .cloned()
+                .collect(),
+            winning_team: self.winning_team,
+            winners: self.winners(),
+            survivors: self.survivors(),
+            fled_fighter: self.fled_fighter().cloned(),
+        }
+    }
+
+    fn advance_turn(&mut self) {
+        loop {
+            self.turn += 1;
+
+            if self.turn >= self.turn_order.len() {
+                self.turn_order = self.generate_turn_order();
+                self.turn = 0;
+            }
+
+            self.current_fighter = self.turn_order[self.turn];
+
+            if self.fighters[self.current_fighter].health.value > 0 {
+                break;
+            }
+        }
+
+        self.ensure_valid_target();
+    }
+
+    pub fn current_fighter(&self) -> &Fighter {
+        self.fighters.get(self.current_fighter).unwrap()
+    }
+
+    pub fn current_fighter_mut(&mut self) -> &mut Fighter {
+        self.fighters.get_mut(self.current_fighter).unwrap()
+    }
+
+    fn target_index(&self) -> usize {
+        self.current_fighter()
+            .target_index
+            .expect("Battle needs two or more fighters with a valid target_index")
+    }
+
+    pub fn target_fighter(&self) -> &Fighter {
+        self.fighters.get(self.target_index()).unwrap()
+    }
+
+    pub fn target_fighter_mut(&mut self) -> &mut Fighter {
+        let index = self.target_index();
+        self.fighters.get_mut(index).unwrap()
+    }
+
+    /// Lets the current anomaly pick its target and action. Players choose through the front-end.
+    pub fn choose_ai_action(&mut self) -> ActionType {
+        let mut rng = self.rng.clone();
+        let fighter = self.current_fighter().clone();
+
+        if let Some(target) = fighter.choose_target(self, &mut rng) {
+            self.current_fighter_mut().target_index = Some(target);
+        }
+
+        let action = fighter.choose_action_type(self, &mut rng);
+        self.rng = rng;
+
+        action
+    }
+
+    fn use_skill(&mut self, round: &mut Round, skill: Skill, dodged: bool, critical: bool) {
+        let fighter = self.current_fighter().clone();
+        let fighter_index = self.current_fighter;
+        let target_index = self.target_index();
+
+        if !fighter.can_afford(&skill) {
+            round.events.push(BattleEvent::NotEnoughMana {
+                fighter: fighter_index,
+                skill: skill.skill_type,
+            });
+            return;
+        }
+
+        self.current_fighter_mut().remove_mana(skill.mana_cost);
+        round.events.push(BattleEvent::SkillUsed {
+            fighter: fighter_index,
+            skill: skill.skill_type,
+        });
+
+        if dodged && skill.deals_damage() {
+            round.events.push(BattleEvent::Dodge {
+                fighter: target_index,
+            });
+        }
+
+        for effect in skill.effects.iter().copied() {
+            match effect {
+                SkillEffect::Damage {
+                    strength,
+                    intelligence,
+                } => {
+                    if dodged {
+                        continue;
+                    }
+
+                    let effectiveness = self.target_fighter().effectiveness_of(skill.element);
+                    let damage = fighter.calculate_skill_damage(
+                        strength,
+                        intelligence,
+                        critical,
+                        &mut self.rng,
+                    );
+                    let damage = (damage as f32 * effectiveness.multiplier()) as i32;
+                    let damage = self.target_fighter().calculate_received_damage(damage);
+                    self.target_fighter_mut().take_damage(damage);
+
+                    round.events.push(BattleEvent::Damage {
+                        fighter: target_index,
+                        amount: damage,
+                        critical,
+                    });
+                    self.push_effectiveness(round, target_index, effectiveness);
+
+                    self.apply_damage_passives(round, fighter_index, target_index, damage);
+                }
+                SkillEffect::Heal { base, intelligence } => {
+                    let amount = fighter.calculate_skill_heal(base, intelligence);
+                    self.current_fighter_mut().heal(amount);
+
+                    round.events.push(BattleEvent::Heal {
+                        fighter: fighter_index,
+                        amount,
+                    });
+                }
+                SkillEffect::DrainMana(amount) => {
+                    if dodged {
+                        continue;
+                    }
+
+                    self.target_fighter_mut().remove_mana(amount);
+
+                    round.events.push(BattleEvent::ManaDrain {
+                        fighter: target_index,
+                        amount,
+                    });
+                }
+                SkillEffect::InflictStatus(infliction) => {
+                    if dodged {
+                        continue;
+                    }
+
+                    self.inflict_statuses(round, target_index, &[infliction]);
+                }
+                SkillEffect::GainStatus(infliction) => {
+                    self.inflict_statuses(round, fighter_index, &[infliction]);
+                }
+            }
+        }
+    }
+
+    fn use_item(&mut self, round: &mut Round, item_type: ItemType) {
+        let fighter_index = self.current_fighter;
+        let Some(item) = get_item_by_type(item_type) else {
+            return;
+        };
+
+        let ItemKind::Consumable { effects } = item.kind else {
+            return;
+        };
+
+        if !self.current_fighter_mut().consume_item(item_type) {
+            return;
+        }
+
+        round.events.push(BattleEvent::ItemUsed {
+            fighter: fighter_index,
+            item: item_type,
+        });
+
+        for effect in effects.iter().copied() {
+            match effect {
+                ItemEffect::RestoreHealth(amount) => {
+                    self.current_fighter_mut().heal(amount);
+
+                    round.events.push(BattleEvent::Heal {
+                        fighter: fighter_index,
+                        amount,
+                    });
+                }
+                ItemEffect::RestoreMana(amount) => {
+                    self.current_fighter_mut().restore_mana(amount);
+
+                    round.events.push(BattleEvent::ManaRestored {
+                        fighter: fighter_index,
+                        amount,
+                    });
+                }
+                ItemEffect::CureStatus(effect_type) => {
+                    if self.current_fighter_mut().remove_status_effect(effect_type) {
+                        round.events.push(BattleEvent::StatusExpired {
+                            fighter: fighter_index,
+                            effect_type,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_effectiveness(&self, round: &mut Round, target: usize, effectiveness: Effectiveness) {
+        if effectiveness != Effectiveness::Neutral {
+            round.events.push(BattleEvent::Effectiveness {
+                fighter: target,
+                effectiveness,
+            });
+        }
+    }
+
+    // Lifesteal heals the attacker and thorns hurt it back, based on the damage dealt
+    fn apply_damage_passives(
+        &mut self,
+        round: &mut Round,
+        attacker: usize,
+        target: usize,
+        damage: i32,
+    ) {
+        if damage <= 0 {
+            return;
+        }
+
+        let lifesteal = self.fighters[attacker].passive_total(|p| match p {
+            PassiveEffect::Lifesteal(percentage) => Some(percentage),
+            _ => None,
+        });
+        let amount = damage * lifesteal / 100;
+
+        if amount > 0 {
+            self.fighters[attacker].heal(amount);
+            round.events.push(BattleEvent::Heal {
+                fighter: attacker,
+                amount,
+            });
+        }
+
+        let thorns = self.fighters[target].passive_total(|p| match p {
+            PassiveEffect::Thorns(percentage) => Some(percentage),
+            _ => None,
+        });
+        let amount = damage * thorns / 100;
+
+        if amount > 0 {
+            self.fighters[attacker].take_damage(amount);
+            round.events.push(BattleEvent::Damage {
+                fighter: attacker,
+                amount,
+                critical: false,
+            });
+        }
+    }
+
+    fn regenerate_mana(&mut self, round: &mut Round) {
+        let fighter_index = self.current_fighter;
+        let fighter = self.current_fighter_mut();
+        let amount = fighter.passive_total(|p| match p {
+            PassiveEffect::ManaRegeneration(amount) => Some(amount),
+            _ => None,
+        });
+
+        if amount <= 0 || fighter.health.value == 0 || fighter.mana.value >= fighter.mana.max {
+            return;
+        }
+
+        fighter.restore_mana(amount);
+        round.events.push(BattleEvent::ManaRestored {
+            fighter: fighter_index,
+            amount,
+        });
+    }
+
+    fn inflict_statuses(
+        &mut self,
+        round: &mut Round,
+        fighter_index: usize,
+        inflictions: &[StatusInfliction],
+    ) {
+        for infliction in inflictions {
+            if !infliction.probability.generate_random_bool(&mut self.rng) {
+                continue;
+            }
+
+            let effect = infliction.to_effect();
+            self.fighters[fighter_index].add_status_effect(effect);
+
+            round.events.push(BattleEvent::StatusInflicted {
+                fighter: fighter_index,
+                effect,
+            });
+        }
+    }
+
+    fn tick_status_effects(&mut self, round: &mut Round, timing: EffectTiming) {
+        let fighter_index = self.current_fighter;
+        let fighter = self.current_fighter_mut();
+
+        for effect in fighter.effects.clone() {
+            if effect.effect_type.timing() != timing {
+                continue;
+            }
+
+            let amount = match effect.effect_type {
+                StatusEffectType::Poison | StatusEffectType::Burn => {
+                    let amount = effect.calculate_tick_amount(fighter.health.max);
+                    fighter.take_damage(amount);
+                    amount
+                }
+                StatusEffectType::Regeneration => {
+                    let amount = effect.calculate_tick_amount(fighter.health.max);
+                    fighter.heal(amount);
+                    amount
+                }
+                StatusEffectType::Stun => 0,
+            };
+
+            round.events.push(BattleEvent::StatusTick {
+                fighter: fighter_index,
+                effect_type: effect.effect_type,
+                amount,
+            });
+        }
+    }
+
+    fn expire_status_effects(&mut self, round: &mut Round) {
+        let fighter_index = self.current_fighter;
+        let fighter = self.current_fighter_mut();
+
+        for effect in fighter.effects.iter_mut() {
+            effect.turns -= 1;
+
+            if effect.turns <= 0 {
+                round.events.push(BattleEvent::StatusExpired {
+                    fighter: fighter_index,
+                    effect_type: effect.effect_type,
+                });
+            }
+        }
+
+        fighter.effects.retain(|e| e.turns > 0);
+    }
+
+    fn update_boss_phases(&mut self, round: &mut Round) {
+        for i in 0..self.fighters.len() {
+            // A single big hit may go through more than one phase
+            while let Some(phase) = self.fighters[i].try_advance_boss_phase() {
+                round
+                    .events
+                    .push(BattleEvent::BossPhase { fighter: i, phase });
+            }
+        }
+    }
+
+    /// Whether the current fighter is able to take the action, skills must be ones it knows
+    pub fn can_take_action(&self, action_type: ActionType) -> bool {
+        match action_type {
+            ActionType::Skill(skill) => self.current_fighter().skills.contains(&skill),
+            ActionType::UseItem(item) => self.current_fighter().can_use_item(item),
+            _ => true,
+        }
+    }
+
+    pub fn run_action(&mut self, action_type: ActionType) -> Result<Round, DynamicError> {
+        if !self.can_take_action(action_type) {
+            Err("Invalid action")?;
+        }
+
+        Ok(self.run_turn(Some(action_type)))
+    }
+
+    // Used when the current fighter cannot act, like when stunned
+    pub fn skip_turn(&mut self) -> Round {
+        self.run_turn(None)
+    }
+
+    fn run_turn(&mut self, action_type: Option<ActionType>) -> Round {
+        // Defending only lasts until the fighter's next turn
+        self.current_fighter_mut().defending = false;
+
+        let stunned = self.current_fighter().is_stunned();
+        let mut round = Round::new(
+            self.current_fighter,
+            self.current_fighter().target_index,
+            None,
+        );
+
+        self.tick_status_effects(&mut round, EffectTiming::TurnStart);
+        let can_act = !stunned && self.current_fighter().health.value > 0;
+
+        if let Some(action_type) = action_type.filter(|_| can_act) {
+            round.action = Some(action_type);
+            self.execute_action(&mut round, action_type);
+        }
+
+        self.tick_status_effects(&mut round, EffectTiming::TurnEnd);
+        self.regenerate_mana(&mut round);
+        self.expire_status_effects(&mut round);
+        self.update_boss_phases(&mut round);
+
+        let alive_teams = self.alive_teams();
+        match alive_teams.len() {
+            1 => self.winning_team = alive_teams.first().copied(),
+            // The fighter fell in the same turn as its last opponent, like to thorns or a status
+            // effect, so the battle goes to the team it was fighting
+            0 => self.winning_team = Some(self.target_fighter().team),
+            _ => {}
+        }
+
+        self.rounds.push(round.clone());
+
+        if !self.finished() {
+            self.advance_turn();
+        }
+
+        round
+    }
+
+    fn execute_action(&mut self, round: &mut Round, action_type: ActionType) {
+        let fighter = self.current_fighter().clone();
+        let fighter_index = self.current_fighter;
+        let target_index = self.target_index();
+
+        let dodged = self
+            .target_fighter()
+            .calculate_dodge_chance(&fighter)
+            .generate_random_bool(&mut self.rng);
+        let critical = fighter
+            .calculate_critical_chance(self.target_fighter())
+            .generate_random_bool(&mut self.rng);
+
+        let strikes = match action_type {
+            ActionType::Attack => true,
+            ActionType::Skill(skill) => {
+                get_skill_by_type(skill).is_some_and(|s| s.deals_damage() && fighter.can_afford(&s))
+            }
+            _ => false,
+        };
+        let critical = critical
+            || (strikes
+                && !fighter.has_struck
+                && fighter.has_passive(PassiveEffect::FirstStrikeCritical));
+
+        if strikes {
+            self.current_fighter_mut().has_struck = true;
+        }
+
+        match action_type {
+            ActionType::Attack => {
+                let effectiveness = self.target_fighter().effectiveness_of(fighter.element);
+                let damage = if dodged {
+                    0
+                } else {
+                    let damage = fighter.calculate_damage(critical, &mut self.rng);
+                    let damage = (damage as f32 * effectiveness.multiplier()) as i32;
+                    self.target_fighter().calculate_received_damage(damage)
+                };
+
+                round.events.push(BattleEvent::Attack {
+                    attacker: fighter_index,
+                    target: target_index,
+                    damage,
+                    critical,
+                });
+
+                if dodged {
+                    round.events.push(BattleEvent::Dodge {
+                        fighter: target_index,
+                    });
+                    return;
+                }
+
+                self.push_effectiveness(round, target_index, effectiveness);
+
+                self.target_fighter_mut().take_damage(damage);
+                self.apply_damage_passives(round, fighter_index, target_index, damage);
+
+                if let Some(anomaly) = fighter.anomaly {
+                    self.inflict_statuses(round, target_index, anomaly.definition.inflicts);
+                }
+            }
+            ActionType::Defend => {
+                self.current_fighter_mut().defending = true;
+
+                round.events.push(BattleEvent::Defend {
+                    fighter: fighter_index,
+                });
+            }
+            ActionType::Flee => {
+                let chance = fighter.calculate_flee_chance(self.target_fighter());
+
+                if chance.generate_random_bool(&mut self.rng) {
+                    self.fled_fighter = Some(fighter_index);
+                    round.events.push(BattleEvent::Fled {
+                        fighter: fighter_index,
+                    });
+                } else {
+                    round.events.push(BattleEvent::FleeFailed {
+                        fighter: fighter_index,
+                        chance,
+                    });
+                }
+            }
+            ActionType::Skill(skill_type) => {
+                let skill = get_skill_by_type(skill_type).expect("Invalid skill type");
+                self.use_skill(round, skill, dodged, critical);
+            }
+            ActionType::UseItem(item_type) => self.use_item(round, item_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use data::{
+        anomalies::{generate_anomaly_from_definition, WOLF},
+        items::ItemType,
+        skills::SkillType,
+        Stat,
+    };
+
+    use super::*;
+
+    fn player(name: &str, items: BTreeMap<ItemType, i32>) -> Fighter {
+        let stats = PlayerStats {
+            health: Stat {
+                max: 100,
+                value: 100,
+            },
+            mana: Stat { max: 50, value: 50 },
+            strength: 10,
+            agility: 10,
+            intelligence: 10,
+            skills: vec![SkillType::HeavyStrike],
+            passives: Vec::new(),
+            items,
+        };
+
+        Fighter::create_from_player(0, name, stats)
+    }
+
+    fn duel() -> Battle {
+        let mut opponent = player("Oponente", BTreeMap::new());
+        opponent.team = ANOMALY_TEAM;
+
+        Battle::new(vec![player("Jogador", BTreeMap::new()), opponent], 0).unwrap()
+    }
+
+    #[test]
+    fn players_only_carry_consumables() {
+        let items = BTreeMap::from([(ItemType::HealthPotion, 2), (ItemType::Wood, 5)]);
+        let fighter = player("Jogador", items);
+
+        assert_eq!(fighter.items, BTreeMap::from([(ItemType::HealthPotion, 2)]));
+    }
+
+    #[test]
+    fn battle_needs_two_teams() {
+        let fighters = vec![
+            player("Jogador", BTreeMap::new()),
+            player("Outro", BTreeMap::new()),
+        ];
+
+        assert!(Battle::new(fighters, 0).is_err());
+    }
+
+    #[test]
+    fn fighters_only_use_their_skills() {
+        let mut battle = duel();
+
+        assert!(battle.can_take_action(ActionType::Skill(SkillType::HeavyStrike)));
+        assert!(!battle.can_take_action(ActionType::Skill(SkillType::Fireball)));
+        assert!(battle
+            .run_action(ActionType::Skill(SkillType::Fireball))
+            .is_err());
+    }
+
+    #[test]
+    fn fighters_only_use_items_they_carry() {
+        let mut opponent = player("Oponente", BTreeMap::new());
+        opponent.team = ANOMALY_TEAM;
+        let items = BTreeMap::from([(ItemType::HealthPotion, 1)]);
+        let mut battle = Battle::new(vec![player("Jogador", items), opponent], 0).unwrap();
+        let fighter = battle.current_fighter_index();
+
+        assert!(!battle.can_take_action(ActionType::UseItem(ItemType::ManaPotion)));
+        assert!(battle
+            .run_action(ActionType::UseItem(ItemType::ManaPotion))
+            .is_err());
+        assert_eq!(battle.current_fighter_index(), fighter);
+
+        battle.current_fighter_mut().items = BTreeMap::from([(ItemType::HealthPotion, 0)]);
+        assert!(!battle.can_take_action(ActionType::UseItem(ItemType::HealthPotion)));
+    }
+
+    #[test]
+    fn only_standing_winners_survive() {
+        let mut opponent = player("Oponente", BTreeMap::new());
+        opponent.team = ANOMALY_TEAM;
+        let fighters = vec![
+            player("Jogador", BTreeMap::new()),
+            player("Aliado", BTreeMap::new()),
+            opponent,
+        ];
+        let mut battle = Battle::new(fighters, 0).unwrap();
+
+        battle.fighters[1].health.value = 0;
+        battle.fighters[2].health.value = 0;
+        battle.skip_turn();
+
+        let result = battle.result();
+        assert_eq!(result.winning_team, Some(PLAYER_TEAM));
+        assert_eq!(result.winners.len(), 2);
+        assert_eq!(result.survivors.len(), 1);
+        assert_eq!(result.survivors[0].name, "Jogador");
+    }
+
+    #[test]
+    fn mutual_knockout_goes_to_the_defender() {
+        let mut battle = duel();
+        let attacker = battle.current_fighter_index();
+        let defender = battle.opponents_of(attacker)[0];
+
+        battle.fighters[attacker].health.value = 1;
+        battle.fighters[defender].health.value = 1;
+        battle.fighters[defender].passives = vec![PassiveEffect::Thorns(100)];
+
+        // Attacks until one lands, then thorns knock out the attacker too
+        while !battle.finished() {
+            if battle.current_fighter_index() == attacker {
+                battle.run_action(ActionType::Attack).unwrap();
+            } else {
+                battle.skip_turn();
+            }
+        }
+
+        assert_eq!(battle.fighters()[attacker].health.value, 0);
+        assert_eq!(battle.fighters()[defender].health.value, 0);
+        assert_eq!(battle.winning_team(), Some(battle.fighters()[defender].team));
+    }
+
+    #[test]
+    fn party_battles_end_when_nobody_is_left_standing() {
+        let mut opponent = player("Oponente", BTreeMap::new());
+        opponent.team = ANOMALY_TEAM;
+        opponent.health.value = 1;
+        opponent.passives = vec![PassiveEffect::Thorns(100)];
+
+        let fighters = vec![
+            player("Jogador", BTreeMap::new()),
+            player("Aliado", BTreeMap::new()),
+            opponent,
+        ];
+        let mut battle = Battle::new(fighters, 0).unwrap();
+
+        battle.fighters[1].health.value = 0;
+        battle.fighters[0].health.value = 1;
+
+        while !battle.finished() {
+            if battle.current_fighter_index() == 0 {
+                battle.run_action(ActionType::Attack).unwrap();
+            } else {
+                battle.skip_turn();
+            }
+        }
+
+        let result = battle.result();
+        assert_eq!(result.winning_team, Some(ANOMALY_TEAM));
+        assert!(result.survivors.is_empty());
+        assert_eq!(result.defeated_fighters.len(), 3);
+    }
+
+    #[test]
+    fn thorns_and_lifesteal_battles_end_with_a_winner() {
+        let anomaly = generate_anomaly_from_definition(&mut StdRng::seed_from_u64(1), &WOLF, 5);
+        let mut anomaly = Fighter::create_from_anomaly(anomaly);
+        anomaly.health.value = 5;
+        anomaly.passives = vec![PassiveEffect::Lifesteal(10)];
+
+        // Low enough that a single hit ends the fight, and thorns reflect it back onto the wolf
+        let mut defender = player("Jogador", BTreeMap::new());
+        defender.health.value = 5;
+        defender.passives = vec![PassiveEffect::Thorns(100)];
+
+        let mut battle = Battle::new(vec![defender, anomaly], 42).unwrap();
+
+        while !battle.finished() {
+            if battle.current_fighter().is_stunned() {
+                battle.skip_turn();
+            } else if battle.current_fighter().user_id.is_none() {
+                let action = battle.choose_ai_action();
+                battle.run_action(action).unwrap();
+            } else {
+                battle.run_action(ActionType::Attack).unwrap();
+            }
+        }
+
+        assert!(battle.finished());
+        let winning_team = battle.winning_team().expect("Battle ended without a winner");
+        assert!(battle
+            .fighters()
+            .iter()
+            .filter(|f| f.team != winning_team)
+            .all(|f| f.health.value == 0));
+    }
+
+    #[test]
+    fn replay_reproduces_the_battle() {
+        let anomaly = generate_anomaly_from_definition(&mut StdRng::seed_from_u64(1), &WOLF, 5);
+        let fighters = vec![
+            player("Jogador", BTreeMap::new()),
+            Fighter::create_from_anomaly(anomaly),
+        ];
+        let mut battle = Battle::new(fighters, 42).unwrap();
+        let mut use_skill = false;
+
+        while !battle.finished() {
+            if battle.current_fighter().is_stunned() {
+                battle.skip_turn();
+            } else if battle.current_fighter().user_id.is_none() {
+                let action = battle.choose_ai_action();
+                battle.run_action(action).unwrap();
+            } else {
+                let action = if use_skill {
+                    ActionType::Skill(SkillType::HeavyStrike)
+                } else {
+                    ActionType::Attack
+                };
+                battle.run_action(action).unwrap();
+                use_skill = !use_skill;
+            }
+        }
+
+        let replayed = Battle::replay(
+            battle.initial_fighters().clone(),
+            battle.seed(),
+            battle.rounds(),
+        )
+        .unwrap();
+
+        assert_eq!(replayed.rounds(), battle.rounds());
+        assert_eq!(replayed.fighters(), battle.fighters());
+        assert_eq!(replayed.winning_team(), battle.winning_team());
+    }
+}
@@ -1,9 +1,17 @@
 use std::fmt::Display;
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use serde::{Serialize, Deserialize};
 
-use crate::{Emoji, Stat, regions::RegionType};
+use crate::{
+    bosses::BOSSES,
+    effects::{StatusEffectType, StatusInfliction},
+    elements::Element,
+    items::{get_item_by_type, ItemKind, ItemType},
+    regions::RegionType,
+    skills::SkillType,
+    Emoji, Probability, Stat,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AnomalyType {
@@ -19,10 +27,13 @@ pub enum AnomalyType {
     Oozeling,
     Nightfall,
     Timberwraith,
+    OrcWarlord,
+    SwampMatriarch,
+    ElderTreant,
 }
 
 impl AnomalyType {
-    const fn name(&self) -> &'static str {
+    pub(crate) const fn name(&self) -> &'static str {
         match self {
             AnomalyType::Treant => "Treant",
             AnomalyType::Wolf => "Lobo",
@@ -36,6 +47,9 @@ impl AnomalyType {
             AnomalyType::SwampFerak => "Ferak do Pântano",
             AnomalyType::Oozeling => "Oozeling",
             AnomalyType::Nightfall => "Nightfall",
+            AnomalyType::OrcWarlord => "Senhor da Guerra Orc",
+            AnomalyType::SwampMatriarch => "Matriarca do Pântano",
+            AnomalyType::ElderTreant => "Treant Ancião",
         }
     }
 
@@ -53,16 +67,114 @@ impl AnomalyType {
             AnomalyType::Oozeling => "https://i.imgur.com/hro2X3W.png",
             AnomalyType::Nightfall => "https://i.imgur.com/ddX6CCu.png",
             AnomalyType::Timberwraith => "https://i.imgur.com/w6xnlJf.png",
+            AnomalyType::OrcWarlord => "https://i.imgur.com/wIfjahq.png",
+            AnomalyType::SwampMatriarch => "https://i.imgur.com/aJsLltd.png",
+            AnomalyType::ElderTreant => "https://i.imgur.com/QuWuU0j.png",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AnomalyVariant {
     Ghost,
     Giant,
 }
 
+pub const ALL_VARIANTS: [AnomalyVariant; 2] = [AnomalyVariant::Ghost, AnomalyVariant::Giant];
+
+impl AnomalyVariant {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            AnomalyVariant::Ghost => "Fantasma",
+            AnomalyVariant::Giant => "Gigante",
+        }
+    }
+
+    pub const fn description(&self) -> &'static str {
+        match self {
+            AnomalyVariant::Ghost => "Fantasma: difícil de acertar, mas tão frágil quanto uma anomalia comum.",
+            AnomalyVariant::Giant => "Gigante: muito mais vida e força, mas bem mais lenta.",
+        }
+    }
+
+    /// Chance of an anomaly being born as this variant
+    pub const fn rarity(&self) -> Probability {
+        match self {
+            AnomalyVariant::Ghost => Probability::new(4),
+            AnomalyVariant::Giant => Probability::new(7),
+        }
+    }
+
+    /// Extra dodge chance added on top of the one given by agility
+    pub const fn dodge_bonus(&self) -> u8 {
+        match self {
+            AnomalyVariant::Ghost => 35,
+            AnomalyVariant::Giant => 0,
+        }
+    }
+
+    /// Extra chance of each loot entry dropping
+    pub const fn loot_bonus(&self) -> u8 {
+        match self {
+            AnomalyVariant::Ghost => 20,
+            AnomalyVariant::Giant => 10,
+        }
+    }
+
+    pub const fn reward_multiplier(&self) -> f32 {
+        match self {
+            AnomalyVariant::Ghost => 2.0,
+            AnomalyVariant::Giant => 1.6,
+        }
+    }
+}
+
+pub fn generate_random_variant(rng: &mut impl Rng) -> Option<AnomalyVariant> {
+    ALL_VARIANTS
+        .iter()
+        .copied()
+        .find(|v| v.rarity().generate_random_bool(rng))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyBehavior {
+    /// Always goes for its strongest affordable attack
+    Aggressive,
+    /// Defends itself once its health percentage drops below the threshold
+    Defensive { health_threshold: i32 },
+    /// Heals itself once its health percentage drops below the threshold and it has mana
+    Healer { health_threshold: i32 },
+}
+
+impl AnomalyBehavior {
+    pub const fn description(&self) -> &'static str {
+        match self {
+            AnomalyBehavior::Aggressive => "Agressiva: sempre busca causar o máximo de dano possível.",
+            AnomalyBehavior::Defensive { .. } => "Defensiva: se protege quando está com pouca vida.",
+            AnomalyBehavior::Healer { .. } => "Curandeira: se cura quando está com pouca vida e tem mana.",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LootEntry {
+    pub item: ItemType,
+    pub probability: Probability,
+    pub min_amount: i32,
+    pub max_amount: i32,
+}
+
+impl LootEntry {
+    pub const fn new(item: ItemType, probability: u8, min_amount: i32, max_amount: i32) -> Self {
+        Self {
+            item,
+            probability: Probability::new(probability),
+            min_amount,
+            max_amount,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AnomalyDefinition {
     pub anomaly_type: AnomalyType,
@@ -71,7 +183,14 @@ pub struct AnomalyDefinition {
     pub strength: i32,
     pub agility: i32,
     pub intelligence: i32,
-    pub valid_regions: &'static [RegionType]
+    pub valid_regions: &'static [RegionType],
+    pub element: Element,
+    /// Status effects that the anomaly may inflict when its attacks hit
+    pub inflicts: &'static [StatusInfliction],
+    pub behavior: AnomalyBehavior,
+    pub skills: &'static [SkillType],
+    /// Items that may drop when the anomaly is defeated, each rolled on its own
+    pub loot: &'static [LootEntry],
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -105,16 +224,82 @@ pub struct Anomaly {
     pub intelligence: i32,
     pub level: i32,
     pub rewards: AnomalyDrops,
+    /// Whether the anomaly was found in the region of its element
+    pub at_home: bool,
 }
 
 impl Anomaly {
-    pub fn name(&self) -> &'static str {
-        self.anomaly_type.name()
+    pub fn name(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("{} {}", self.anomaly_type.name(), variant.name()),
+            None => self.anomaly_type.name().to_string(),
+        }
+    }
+
+    pub fn apply_variant(&mut self, variant: AnomalyVariant) {
+        match variant {
+            AnomalyVariant::Giant => {
+                self.health = Stat::new((self.health.max as f32 * 1.8) as i32);
+                self.strength = (self.strength as f32 * 1.5) as i32;
+                self.agility = ((self.agility as f32 * 0.5) as i32).max(1);
+            }
+            // The dodge bonus is handled by the battle itself
+            AnomalyVariant::Ghost => {}
+        }
+
+        self.rewards.xp = (self.rewards.xp as f32 * variant.reward_multiplier()) as i32;
+        self.rewards.gold = (self.rewards.gold as f32 * variant.reward_multiplier()) as i32;
+        self.variant = Some(variant);
     }
 
     pub fn image(&self) -> &'static str {
         self.anomaly_type.image()
     }
+
+    pub fn element(&self) -> Element {
+        self.definition.element
+    }
+
+    pub fn loot_chance(&self, entry: &LootEntry) -> Probability {
+        let bonus = self.variant.map_or(0, |v| v.loot_bonus());
+        Probability::new(entry.probability.value().saturating_add(bonus))
+    }
+
+    // Stronger anomalies drop more materials, equipment always drops alone
+    fn loot_amount_multiplier(&self) -> f32 {
+        (1.0 + self.level as f32 / 20.0) * self.variant.map_or(1.0, |v| v.reward_multiplier())
+    }
+
+    pub fn roll_loot(&self, rng: &mut impl Rng) -> Vec<(ItemType, i32)> {
+        let mut drops = Vec::new();
+
+        for entry in self.definition.loot {
+            if !self.loot_chance(entry).generate_random_bool(rng) {
+                continue;
+            }
+
+            let mut amount = rng.gen_range(entry.min_amount..=entry.max_amount);
+            if get_item_by_type(entry.item).is_some_and(|i| i.kind == ItemKind::Material) {
+                amount = (amount as f32 * self.loot_amount_multiplier()) as i32;
+            }
+
+            drops.push((entry.item, amount.max(1)));
+        }
+
+        drops
+    }
+
+    pub fn display_loot(&self) -> String {
+        self.definition
+            .loot
+            .iter()
+            .filter_map(|entry| {
+                let item = get_item_by_type(entry.item)?;
+                Some(format!("{} {} (`{}`)", item.emoji, item.name, self.loot_chance(entry)))
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -128,7 +313,12 @@ pub const TREANT: AnomalyDefinition = AnomalyDefinition {
     strength: 10,
     agility: 1,
     intelligence: 3,
-    valid_regions: &[RegionType::Grassland]
+    valid_regions: &[RegionType::Grassland],
+    element: Element::Nature,
+    inflicts: &[],
+    behavior: AnomalyBehavior::Healer { health_threshold: 40 },
+    skills: &[SkillType::Regrowth],
+    loot: &[LootEntry::new(ItemType::Wood, 70, 1, 3), LootEntry::new(ItemType::AncientBark, 5, 1, 1)],
 };
 
 pub const WOLF: AnomalyDefinition = AnomalyDefinition {
@@ -138,7 +328,12 @@ pub const WOLF: AnomalyDefinition = AnomalyDefinition {
     strength: 5,
     agility: 3,
     intelligence: 3,
-    valid_regions: &[RegionType::Forest]
+    valid_regions: &[RegionType::Forest],
+    element: Element::Nature,
+    inflicts: &[],
+    behavior: AnomalyBehavior::Aggressive,
+    skills: &[SkillType::FeralBite],
+    loot: &[LootEntry::new(ItemType::WolfFang, 50, 1, 2)],
 };
 
 pub const BLOB: AnomalyDefinition = AnomalyDefinition {
@@ -148,7 +343,12 @@ pub const BLOB: AnomalyDefinition = AnomalyDefinition {
     strength: 8,
     agility: 2,
     intelligence: 5,
-    valid_regions: &[RegionType::Swamp]
+    valid_regions: &[RegionType::Swamp],
+    element: Element::Water,
+    inflicts: &[StatusInfliction::new(StatusEffectType::Poison, 20, 2, 4)],
+    behavior: AnomalyBehavior::Defensive { health_threshold: 30 },
+    skills: &[SkillType::AcidSpit],
+    loot: &[LootEntry::new(ItemType::Slime, 70, 1, 3)],
 };
 
 pub const SKELETON: AnomalyDefinition = AnomalyDefinition {
@@ -158,7 +358,12 @@ pub const SKELETON: AnomalyDefinition = AnomalyDefinition {
     strength: 10,
     agility: 10,
     intelligence: 5,
-    valid_regions: &[RegionType::Forest, RegionType::Swamp]
+    valid_regions: &[RegionType::Forest, RegionType::Swamp],
+    element: Element::Shadow,
+    inflicts: &[],
+    behavior: AnomalyBehavior::Defensive { health_threshold: 35 },
+    skills: &[],
+    loot: &[LootEntry::new(ItemType::IronOre, 30, 1, 2), LootEntry::new(ItemType::HealthPotion, 10, 1, 1)],
 };
 
 pub const ORC: AnomalyDefinition = AnomalyDefinition {
@@ -168,7 +373,12 @@ pub const ORC: AnomalyDefinition = AnomalyDefinition {
     strength: 20,
     agility: 5,
     intelligence: 3,
-    valid_regions: &[RegionType::Forest]
+    valid_regions: &[RegionType::Forest],
+    element: Element::Earth,
+    inflicts: &[],
+    behavior: AnomalyBehavior::Aggressive,
+    skills: &[SkillType::HeavyStrike],
+    loot: &[LootEntry::new(ItemType::IronOre, 50, 1, 3), LootEntry::new(ItemType::WoodenSword, 5, 1, 1)],
 };
 
 pub const VEXBUG: AnomalyDefinition = AnomalyDefinition {
@@ -178,7 +388,12 @@ pub const VEXBUG: AnomalyDefinition = AnomalyDefinition {
     strength: 10,
     agility: 3,
     intelligence: 15,
-    valid_regions: &[RegionType::Forest]
+    valid_regions: &[RegionType::Forest],
+    element: Element::Nature,
+    inflicts: &[],
+    behavior: AnomalyBehavior::Aggressive,
+    skills: &[SkillType::AcidSpit],
+    loot: &[LootEntry::new(ItemType::Slime, 40, 1, 2), LootEntry::new(ItemType::Antidote, 15, 1, 1)],
 };
 
 pub const GUARDIAN: AnomalyDefinition = AnomalyDefinition {
@@ -188,7 +403,12 @@ pub const GUARDIAN: AnomalyDefinition = AnomalyDefinition {
     strength: 12,
     agility: 5,
     intelligence: 10,
-    valid_regions: &[RegionType::Forest]
+    valid_regions: &[RegionType::Forest],
+    element: Element::Light,
+    inflicts: &[StatusInfliction::new(StatusEffectType::Stun, 15, 1, 0)],
+    behavior: AnomalyBehavior::Healer { health_threshold: 50 },
+    skills: &[SkillType::Heal],
+    loot: &[LootEntry::new(ItemType::IronOre, 40, 1, 2), LootEntry::new(ItemType::ManaPotion, 20, 1, 1)],
 };
 
 pub const FERAK: AnomalyDefinition = AnomalyDefinition {
@@ -198,7 +418,12 @@ pub const FERAK: AnomalyDefinition = AnomalyDefinition {
     strength: 30,
     agility: 5,
     intelligence: 1,
-    valid_regions: &[RegionType::Swamp, RegionType::Forest]
+    valid_regions: &[RegionType::Swamp, RegionType::Forest],
+    element: Element::Earth,
+    inflicts: &[],
+    behavior: AnomalyBehavior::Aggressive,
+    skills: &[SkillType::FeralBite],
+    loot: &[LootEntry::new(ItemType::WolfFang, 40, 1, 2), LootEntry::new(ItemType::LeatherArmor, 4, 1, 1)],
 };
 
 pub const SWAMP_FERAK: AnomalyDefinition = AnomalyDefinition {
@@ -208,7 +433,12 @@ pub const SWAMP_FERAK: AnomalyDefinition = AnomalyDefinition {
     strength: 20,
     agility: 5,
     intelligence: 3,
-    valid_regions: &[RegionType::Swamp]
+    valid_regions: &[RegionType::Swamp],
+    element: Element::Water,
+    inflicts: &[StatusInfliction::new(StatusEffectType::Poison, 35, 3, 5)],
+    behavior: AnomalyBehavior::Aggressive,
+    skills: &[SkillType::AcidSpit],
+    loot: &[LootEntry::new(ItemType::Slime, 40, 1, 2), LootEntry::new(ItemType::Antidote, 20, 1, 1)],
 };
 
 pub const OOZELING: AnomalyDefinition = AnomalyDefinition {
@@ -218,7 +448,12 @@ pub const OOZELING: AnomalyDefinition = AnomalyDefinition {
     strength: 10,
     agility: 15,
     intelligence: 2,
-    valid_regions: &[RegionType::Grassland]
+    valid_regions: &[RegionType::Grassland],
+    element: Element::Water,
+    inflicts: &[StatusInfliction::new(StatusEffectType::Poison, 25, 2, 6)],
+    behavior: AnomalyBehavior::Defensive { health_threshold: 40 },
+    skills: &[SkillType::AcidSpit],
+    loot: &[LootEntry::new(ItemType::Slime, 80, 2, 4)],
 };
 
 pub const NIGHTFALL: AnomalyDefinition = AnomalyDefinition {
@@ -228,7 +463,12 @@ pub const NIGHTFALL: AnomalyDefinition = AnomalyDefinition {
     strength: 30,
     agility: 10,
     intelligence: 10,
-    valid_regions: &[RegionType::Forest]
+    valid_regions: &[RegionType::Forest],
+    element: Element::Shadow,
+    inflicts: &[StatusInfliction::new(StatusEffectType::Burn, 25, 2, 7)],
+    behavior: AnomalyBehavior::Aggressive,
+    skills: &[SkillType::ShadowStrike],
+    loot: &[LootEntry::new(ItemType::ManaPotion, 25, 1, 1), LootEntry::new(ItemType::ShadowDagger, 2, 1, 1)],
 };
 
 pub const TIMBERWRAITH: AnomalyDefinition = AnomalyDefinition {
@@ -238,7 +478,12 @@ pub const TIMBERWRAITH: AnomalyDefinition = AnomalyDefinition {
     strength: 20,
     agility: 15,
     intelligence: 10,
-    valid_regions: &[RegionType::Grassland]
+    valid_regions: &[RegionType::Grassland],
+    element: Element::Nature,
+    inflicts: &[StatusInfliction::new(StatusEffectType::Stun, 10, 1, 0)],
+    behavior: AnomalyBehavior::Healer { health_threshold: 35 },
+    skills: &[SkillType::Regrowth, SkillType::HeavyStrike],
+    loot: &[LootEntry::new(ItemType::Wood, 60, 2, 4), LootEntry::new(ItemType::AncientBark, 10, 1, 1)],
 };
 
 pub const ANOMALIES: [AnomalyDefinition; 12] = [
@@ -257,7 +502,11 @@ pub const ANOMALIES: [AnomalyDefinition; 12] = [
 ];
 
 pub fn get_anomaly_from_type(anomaly: AnomalyType) -> Option<Anomaly> {
-    let anomaly = ANOMALIES.iter().copied().find(|a| a.anomaly_type == anomaly)?;
+    let anomaly = ANOMALIES
+        .iter()
+        .copied()
+        .chain(BOSSES.iter().map(|b| b.anomaly))
+        .find(|a| a.anomaly_type == anomaly)?;
 
     Some(Anomaly {
         definition: anomaly,
@@ -273,6 +522,7 @@ pub fn get_anomaly_from_type(anomaly: AnomalyType) -> Option<Anomaly> {
             xp: 0,
             gold: 0,
         },
+        at_home: false,
     })
 }
 
@@ -282,7 +532,7 @@ pub fn factor(level: i32, factor: f32) -> i32 {
 }
 
 pub fn calculate_potency(
-    rng: &mut ThreadRng,
+    rng: &mut impl Rng,
     health: f32,
     mana: f32,
     strength: f32,
@@ -294,13 +544,30 @@ pub fn calculate_potency(
         / rng.gen_range(10.0..12.0)
 }
 
-pub fn generate_random_anomaly(player_level: i32, region_type: RegionType) -> Anomaly {
+pub fn generate_random_anomaly(rng: &mut impl Rng, player_level: i32, region_type: RegionType) -> Anomaly {
     let valid_anomalies: Vec<&AnomalyDefinition> = ANOMALIES.iter().filter(|a| a.valid_regions.contains(&region_type)).collect();
 
-    let rng = &mut rand::thread_rng();
     let random_index = rng.gen_range(0..valid_anomalies.len());
     let def = valid_anomalies[random_index];
 
+    let mut anomaly = generate_anomaly_from_definition(rng, def, player_level);
+    anomaly.at_home = region_type.element() == def.element;
+
+    anomaly
+}
+
+pub fn generate_anomaly_from_definition(rng: &mut impl Rng, def: &AnomalyDefinition, player_level: i32) -> Anomaly {
+    let mut anomaly = create_anomaly(rng, def, player_level);
+
+    if let Some(variant) = generate_random_variant(rng) {
+        anomaly.apply_variant(variant);
+    }
+
+    anomaly
+}
+
+// Scales the definition to the player's level, without rolling a variant
+pub(crate) fn create_anomaly(rng: &mut impl Rng, def: &AnomalyDefinition, player_level: i32) -> Anomaly {
     let level = (player_level as f32 * rng.gen_range(0.8..1.3)).max(1.0) as i32;
     let health = Stat::new((factor(level, 1.3) * def.health.max) as i32);
     let mana = Stat::new((factor(level, 1.3) * def.mana.max) as i32);
@@ -336,5 +603,6 @@ pub fn generate_random_anomaly(player_level: i32, region_type: RegionType) -> An
             xp: xp_reward,
             gold: gold_reward,
         },
+        at_home: false,
     }
 }
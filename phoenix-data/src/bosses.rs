@@ -0,0 +1,189 @@
+use rand::Rng;
+
+use crate::{
+    anomalies::{self, Anomaly, AnomalyBehavior, AnomalyDefinition, AnomalyType, LootEntry},
+    effects::{StatusEffectType, StatusInfliction},
+    elements::Element,
+    items::ItemType,
+    regions::RegionType,
+    skills::SkillType,
+    Stat,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BossPhase {
+    /// Health percentage at or below which the phase starts
+    pub health_threshold: i32,
+    pub message: &'static str,
+    pub strength_multiplier: f32,
+    pub agility_multiplier: f32,
+    pub behavior: AnomalyBehavior,
+    /// Replaces the skills the boss had in the previous phase
+    pub skills: &'static [SkillType],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BossDefinition {
+    pub anomaly: AnomalyDefinition,
+    /// Region guarded by the boss, it must be defeated before the player can leave it
+    pub region: RegionType,
+    /// Ordered by decreasing health threshold
+    pub phases: &'static [BossPhase],
+    pub reward_multiplier: f32,
+}
+
+impl BossDefinition {
+    pub const fn name(&self) -> &'static str {
+        self.anomaly.anomaly_type.name()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub const ORC_WARLORD: BossDefinition = BossDefinition {
+    anomaly: AnomalyDefinition {
+        anomaly_type: AnomalyType::OrcWarlord,
+        health: Stat::new(120),
+        mana: Stat::new(30),
+        strength: 12,
+        agility: 4,
+        intelligence: 3,
+        valid_regions: &[],
+        element: Element::Earth,
+        inflicts: &[],
+        behavior: AnomalyBehavior::Defensive { health_threshold: 30 },
+        skills: &[SkillType::HeavyStrike],
+        loot: &[
+            LootEntry::new(ItemType::IronOre, 100, 3, 6),
+            LootEntry::new(ItemType::IronSword, 30, 1, 1),
+        ],
+    },
+    region: RegionType::Forest,
+    phases: &[
+        BossPhase {
+            health_threshold: 50,
+            message: "solta um grito de guerra e entra em fúria!",
+            strength_multiplier: 1.3,
+            agility_multiplier: 1.0,
+            behavior: AnomalyBehavior::Aggressive,
+            skills: &[SkillType::HeavyStrike, SkillType::Rampage],
+        },
+        BossPhase {
+            health_threshold: 20,
+            message: "está encurralado e luta com suas últimas forças!",
+            strength_multiplier: 1.2,
+            agility_multiplier: 1.5,
+            behavior: AnomalyBehavior::Aggressive,
+            skills: &[SkillType::Rampage],
+        },
+    ],
+    reward_multiplier: 3.0,
+};
+
+pub const SWAMP_MATRIARCH: BossDefinition = BossDefinition {
+    anomaly: AnomalyDefinition {
+        anomaly_type: AnomalyType::SwampMatriarch,
+        health: Stat::new(100),
+        mana: Stat::new(40),
+        strength: 9,
+        agility: 4,
+        intelligence: 10,
+        valid_regions: &[],
+        element: Element::Water,
+        inflicts: &[StatusInfliction::new(StatusEffectType::Poison, 30, 2, 4)],
+        behavior: AnomalyBehavior::Healer { health_threshold: 70 },
+        skills: &[SkillType::AcidSpit, SkillType::Heal],
+        loot: &[
+            LootEntry::new(ItemType::Slime, 100, 4, 8),
+            LootEntry::new(ItemType::GreaterHealthPotion, 50, 1, 2),
+            LootEntry::new(ItemType::WisdomAmulet, 10, 1, 1),
+        ],
+    },
+    region: RegionType::Swamp,
+    phases: &[
+        BossPhase {
+            health_threshold: 60,
+            message: "afunda na lama e faz o pântano inteiro se agitar!",
+            strength_multiplier: 1.0,
+            agility_multiplier: 1.0,
+            behavior: AnomalyBehavior::Healer { health_threshold: 40 },
+            skills: &[SkillType::ToxicTide, SkillType::Heal],
+        },
+        BossPhase {
+            health_threshold: 25,
+            message: "desiste de se curar e ataca com todo o veneno que lhe resta!",
+            strength_multiplier: 1.3,
+            agility_multiplier: 1.0,
+            behavior: AnomalyBehavior::Aggressive,
+            skills: &[SkillType::ToxicTide, SkillType::EssenceDrain],
+        },
+    ],
+    reward_multiplier: 3.0,
+};
+
+pub const ELDER_TREANT: BossDefinition = BossDefinition {
+    anomaly: AnomalyDefinition {
+        anomaly_type: AnomalyType::ElderTreant,
+        health: Stat::new(150),
+        mana: Stat::new(30),
+        strength: 10,
+        agility: 1,
+        intelligence: 5,
+        valid_regions: &[],
+        element: Element::Nature,
+        inflicts: &[],
+        behavior: AnomalyBehavior::Healer { health_threshold: 40 },
+        skills: &[SkillType::Regrowth],
+        loot: &[
+            LootEntry::new(ItemType::AncientBark, 100, 2, 4),
+            LootEntry::new(ItemType::Wood, 100, 5, 10),
+            LootEntry::new(ItemType::ApprenticeStaff, 20, 1, 1),
+        ],
+    },
+    region: RegionType::Grassland,
+    phases: &[
+        BossPhase {
+            health_threshold: 50,
+            message: "crava suas raízes no chão e faz a planície tremer!",
+            strength_multiplier: 1.2,
+            agility_multiplier: 1.0,
+            behavior: AnomalyBehavior::Healer { health_threshold: 30 },
+            skills: &[SkillType::Regrowth, SkillType::Earthquake],
+        },
+        BossPhase {
+            health_threshold: 20,
+            message: "tem sua casca rachada e perde o controle!",
+            strength_multiplier: 1.4,
+            agility_multiplier: 1.0,
+            behavior: AnomalyBehavior::Aggressive,
+            skills: &[SkillType::Earthquake],
+        },
+    ],
+    reward_multiplier: 3.0,
+};
+
+pub const BOSSES: [BossDefinition; 3] = [ORC_WARLORD, SWAMP_MATRIARCH, ELDER_TREANT];
+
+pub fn get_boss_by_type(anomaly_type: AnomalyType) -> Option<BossDefinition> {
+    BOSSES
+        .iter()
+        .find(|b| b.anomaly.anomaly_type == anomaly_type)
+        .copied()
+}
+
+pub fn get_region_boss(region: RegionType) -> Option<BossDefinition> {
+    BOSSES.iter().find(|b| b.region == region).copied()
+}
+
+// Bosses are never generated as variants
+pub fn generate_boss(rng: &mut impl Rng, boss: &BossDefinition, player_level: i32) -> Anomaly {
+    let mut anomaly = anomalies::create_anomaly(rng, &boss.anomaly, player_level);
+    anomaly.at_home = boss.region.element() == boss.anomaly.element;
+
+    anomaly.rewards.xp = (anomaly.rewards.xp as f32 * boss.reward_multiplier) as i32;
+    anomaly.rewards.gold = (anomaly.rewards.gold as f32 * boss.reward_multiplier) as i32;
+
+    anomaly
+}
@@ -1,6 +1,13 @@
 #![allow(unused)]
 
 use serde::{Deserialize, Serialize};
+
+use crate::{
+    passives::{Passive, PassiveEffect},
+    skills::SkillType,
+    Emoji,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ClassType {
     Knight,
@@ -8,6 +15,45 @@ pub enum ClassType {
     Assassin,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Attribute {
+    Health,
+    Mana,
+    Strength,
+    Agility,
+    Intelligence,
+}
+
+impl Attribute {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Attribute::Health => "Vida",
+            Attribute::Mana => "Mana",
+            Attribute::Strength => "Força",
+            Attribute::Agility => "Agilidade",
+            Attribute::Intelligence => "Inteligência",
+        }
+    }
+
+    pub const fn emoji(&self) -> Emoji {
+        match self {
+            Attribute::Health => Emoji::Health,
+            Attribute::Mana => Emoji::Mana,
+            Attribute::Strength => Emoji::Strength,
+            Attribute::Agility => Emoji::Agility,
+            Attribute::Intelligence => Emoji::Intelligence,
+        }
+    }
+}
+
+pub const ALL_ATTRIBUTES: [Attribute; 5] = [
+    Attribute::Health,
+    Attribute::Mana,
+    Attribute::Strength,
+    Attribute::Agility,
+    Attribute::Intelligence,
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CharacterClass {
     pub name: &'static str,
@@ -19,6 +65,20 @@ pub struct CharacterClass {
     pub strength: i32,
     pub agility: i32,
     pub intelligence: i32,
+    pub skills: &'static [SkillType],
+    pub passive: Passive,
+}
+
+impl CharacterClass {
+    pub const fn base_attribute(&self, attribute: Attribute) -> i32 {
+        match attribute {
+            Attribute::Health => self.health,
+            Attribute::Mana => self.mana,
+            Attribute::Strength => self.strength,
+            Attribute::Agility => self.agility,
+            Attribute::Intelligence => self.intelligence,
+        }
+    }
 }
 
 pub const KNIGHT: CharacterClass = CharacterClass {
@@ -31,6 +91,12 @@ pub const KNIGHT: CharacterClass = CharacterClass {
     strength: 20,
     agility: 5,
     intelligence: 5,
+    skills: &[SkillType::HeavyStrike, SkillType::SecondWind],
+    passive: Passive {
+        name: "Armadura Pesada",
+        emoji: "🛡️",
+        effect: PassiveEffect::DamageReduction(15),
+    },
 };
 
 pub const MAGE: CharacterClass = CharacterClass {
@@ -43,6 +109,12 @@ pub const MAGE: CharacterClass = CharacterClass {
     strength: 5,
     agility: 8,
     intelligence: 15,
+    skills: &[SkillType::Fireball, SkillType::Heal, SkillType::ArcaneDrain],
+    passive: Passive {
+        name: "Meditação",
+        emoji: "🧘",
+        effect: PassiveEffect::ManaRegeneration(4),
+    },
 };
 
 pub const ASSASSIN: CharacterClass = CharacterClass {
@@ -55,6 +127,12 @@ pub const ASSASSIN: CharacterClass = CharacterClass {
     strength: 15,
     agility: 15,
     intelligence: 10,
+    skills: &[SkillType::ShadowStrike, SkillType::EssenceDrain],
+    passive: Passive {
+        name: "Ataque Furtivo",
+        emoji: "🌒",
+        effect: PassiveEffect::FirstStrikeCritical,
+    },
 };
 
 pub const ALL_CLASSES: [CharacterClass; 3] = [KNIGHT, MAGE, ASSASSIN];
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use inflector::Inflector;
-use rand::{Rng, thread_rng, seq::SliceRandom};
+use rand::{Rng, seq::SliceRandom};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,10 +22,10 @@ impl Probability {
         self.0
     }
 
-    pub fn generate_random_bool(&self) -> bool {
+    pub fn generate_random_bool(&self, rng: &mut impl Rng) -> bool {
         let probability = self.0.clamp(0, 100) as f64 / 100f64;
 
-        rand::thread_rng().gen_bool(probability.clamp(0f64, 1f64))
+        rng.gen_bool(probability.clamp(0f64, 1f64))
     }
 }
 
@@ -99,8 +99,7 @@ const VOWELS: [&'static str; 5] = ["a", "e", "i", "o", "u"];
 const CONSONANTS: [&'static str; 24] = ["b", "c", "d", "f", "g", "h", "j", "k", "l", "l", "m", "n", "p", "q", "r", "s", "t", "v", "w", "x", "y", "z", "lh", "ch"];
 const REMAINESCENTS: [&'static str; 5] = ["r", "s", "l", "m", "n"];
 
-pub fn invent_word(syllables: u8) -> Option<String> {
-    let rng = &mut thread_rng();
+pub fn invent_word(rng: &mut impl Rng, syllables: u8) -> Option<String> {
     let mut string = String::new();
 
     for i in 0..=syllables {
@@ -122,8 +121,7 @@ pub fn invent_word(syllables: u8) -> Option<String> {
     Some(string.to_title_case())
 }
 
-pub fn generate_name(prefix: &str, first_suffix: Vec<&str>, possible_second_suffix: Option<Vec<&str>>, possible_last_suffix: Option<Vec<&str>>) -> Option<String> {
-    let rng = &mut thread_rng();
+pub fn generate_name(rng: &mut impl Rng, prefix: &str, first_suffix: Vec<&str>, possible_second_suffix: Option<Vec<&str>>, possible_last_suffix: Option<Vec<&str>>) -> Option<String> {
     let mut name = format!("{prefix} {}", first_suffix.choose(rng)?);
     if let Some(suffixes) = possible_second_suffix {
         if rng.gen_bool(0.7) {
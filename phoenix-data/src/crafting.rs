@@ -0,0 +1,141 @@
+use crate::items::{get_item_by_type, Item, ItemType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ingredient {
+    pub item: ItemType,
+    pub amount: i32,
+}
+
+impl Ingredient {
+    pub const fn new(item: ItemType, amount: i32) -> Self {
+        Self { item, amount }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recipe {
+    pub output: ItemType,
+    /// How many items a single craft produces
+    pub amount: i32,
+    pub ingredients: &'static [Ingredient],
+}
+
+impl Recipe {
+    pub fn item(&self) -> Item {
+        get_item_by_type(self.output).expect("Invalid recipe output")
+    }
+
+    pub fn display_ingredients(&self, amount_of: impl Fn(ItemType) -> i32) -> String {
+        self.ingredients
+            .iter()
+            .filter_map(|i| {
+                let item = get_item_by_type(i.item)?;
+                Some(format!(
+                    "{} {} ({}/{})",
+                    item.emoji,
+                    item.name,
+                    amount_of(i.item),
+                    i.amount
+                ))
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub const ALL_RECIPES: [Recipe; 11] = [
+    Recipe {
+        output: ItemType::HealthPotion,
+        amount: 2,
+        ingredients: &[
+            Ingredient::new(ItemType::Slime, 3),
+            Ingredient::new(ItemType::Wood, 1),
+        ],
+    },
+    Recipe {
+        output: ItemType::GreaterHealthPotion,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::HealthPotion, 2),
+            Ingredient::new(ItemType::AncientBark, 1),
+        ],
+    },
+    Recipe {
+        output: ItemType::ManaPotion,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::Slime, 2),
+            Ingredient::new(ItemType::WolfFang, 1),
+        ],
+    },
+    Recipe {
+        output: ItemType::Antidote,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::Slime, 2),
+            Ingredient::new(ItemType::Wood, 1),
+        ],
+    },
+    Recipe {
+        output: ItemType::WoodenSword,
+        amount: 1,
+        ingredients: &[Ingredient::new(ItemType::Wood, 5)],
+    },
+    Recipe {
+        output: ItemType::IronSword,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::IronOre, 6),
+            Ingredient::new(ItemType::Wood, 2),
+        ],
+    },
+    Recipe {
+        output: ItemType::ApprenticeStaff,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::Wood, 6),
+            Ingredient::new(ItemType::AncientBark, 1),
+        ],
+    },
+    Recipe {
+        output: ItemType::ShadowDagger,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::IronOre, 4),
+            Ingredient::new(ItemType::WolfFang, 6),
+            Ingredient::new(ItemType::AncientBark, 2),
+        ],
+    },
+    Recipe {
+        output: ItemType::LeatherArmor,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::WolfFang, 4),
+            Ingredient::new(ItemType::Wood, 2),
+        ],
+    },
+    Recipe {
+        output: ItemType::IronArmor,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::IronOre, 10),
+            Ingredient::new(ItemType::WolfFang, 2),
+        ],
+    },
+    Recipe {
+        output: ItemType::WisdomAmulet,
+        amount: 1,
+        ingredients: &[
+            Ingredient::new(ItemType::AncientBark, 3),
+            Ingredient::new(ItemType::IronOre, 3),
+        ],
+    },
+];
+
+pub fn get_recipe_by_output(output: ItemType) -> Option<Recipe> {
+    ALL_RECIPES.iter().find(|r| r.output == output).copied()
+}
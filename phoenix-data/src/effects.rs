@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Probability;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusEffectType {
+    Poison,
+    Burn,
+    Stun,
+    Regeneration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectTiming {
+    TurnStart,
+    TurnEnd,
+}
+
+impl StatusEffectType {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            StatusEffectType::Poison => "Veneno",
+            StatusEffectType::Burn => "Queimadura",
+            StatusEffectType::Stun => "Atordoamento",
+            StatusEffectType::Regeneration => "Regeneração",
+        }
+    }
+
+    pub const fn emoji(&self) -> &'static str {
+        match self {
+            StatusEffectType::Poison => "☠️",
+            StatusEffectType::Burn => "🔥",
+            StatusEffectType::Stun => "💫",
+            StatusEffectType::Regeneration => "💚",
+        }
+    }
+
+    pub const fn description(&self) -> &'static str {
+        match self {
+            StatusEffectType::Poison => "causa dano no início de cada rodada",
+            StatusEffectType::Burn => "causa bastante dano no início de cada rodada",
+            StatusEffectType::Stun => "faz perder a próxima rodada",
+            StatusEffectType::Regeneration => "restaura vida no fim de cada rodada",
+        }
+    }
+
+    pub const fn timing(&self) -> EffectTiming {
+        match self {
+            StatusEffectType::Regeneration => EffectTiming::TurnEnd,
+            _ => EffectTiming::TurnStart,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub effect_type: StatusEffectType,
+    pub turns: i32,
+    /// Percentage of the affected fighter's max health dealt or restored on each tick
+    pub potency: i32,
+}
+
+impl StatusEffect {
+    pub fn calculate_tick_amount(&self, max_health: i32) -> i32 {
+        ((max_health * self.potency) / 100).max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusInfliction {
+    pub effect_type: StatusEffectType,
+    pub probability: Probability,
+    pub turns: i32,
+    pub potency: i32,
+}
+
+impl StatusInfliction {
+    pub const fn new(
+        effect_type: StatusEffectType,
+        probability: u8,
+        turns: i32,
+        potency: i32,
+    ) -> Self {
+        Self {
+            effect_type,
+            probability: Probability::new(probability),
+            turns,
+            potency,
+        }
+    }
+
+    pub const fn to_effect(&self) -> StatusEffect {
+        StatusEffect {
+            effect_type: self.effect_type,
+            turns: self.turns,
+            potency: self.potency,
+        }
+    }
+}
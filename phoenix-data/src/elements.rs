@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+/// Extra damage dealt by an anomaly fighting in the region of its own element
+pub const HOME_REGION_DAMAGE_BONUS: f32 = 0.2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Element {
+    #[default]
+    Neutral,
+    Fire,
+    Water,
+    Nature,
+    Earth,
+    Shadow,
+    Light,
+}
+
+pub const ALL_ELEMENTS: [Element; 7] = [
+    Element::Neutral,
+    Element::Fire,
+    Element::Water,
+    Element::Nature,
+    Element::Earth,
+    Element::Shadow,
+    Element::Light,
+];
+
+impl Element {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Element::Neutral => "Neutro",
+            Element::Fire => "Fogo",
+            Element::Water => "Água",
+            Element::Nature => "Natureza",
+            Element::Earth => "Terra",
+            Element::Shadow => "Sombra",
+            Element::Light => "Luz",
+        }
+    }
+
+    pub const fn emoji(&self) -> &'static str {
+        match self {
+            Element::Neutral => "⚪",
+            Element::Fire => "🔥",
+            Element::Water => "💧",
+            Element::Nature => "🌿",
+            Element::Earth => "🪨",
+            Element::Shadow => "🌑",
+            Element::Light => "☀️",
+        }
+    }
+
+    /// How effective an attack of this element is against a target of the given element
+    pub const fn effectiveness_against(&self, target: Element) -> Effectiveness {
+        use Element::*;
+
+        match (*self, target) {
+            (Fire, Nature) | (Water, Fire | Earth) | (Nature, Water | Earth) | (Earth, Fire) => {
+                Effectiveness::Strong
+            }
+            (Shadow, Nature | Light) | (Light, Shadow) => Effectiveness::Strong,
+            (Fire, Water | Earth | Fire)
+            | (Water, Water | Nature)
+            | (Nature, Fire | Nature | Shadow) => Effectiveness::Weak,
+            (Earth, Nature) | (Shadow, Shadow) | (Light, Light) => Effectiveness::Weak,
+            _ => Effectiveness::Neutral,
+        }
+    }
+
+    // Elements that deal extra damage to this one
+    pub fn weaknesses(&self) -> Vec<Element> {
+        ALL_ELEMENTS
+            .iter()
+            .copied()
+            .filter(|e| e.effectiveness_against(*self) == Effectiveness::Strong)
+            .collect()
+    }
+
+    // Elements that deal less damage to this one
+    pub fn resistances(&self) -> Vec<Element> {
+        ALL_ELEMENTS
+            .iter()
+            .copied()
+            .filter(|e| e.effectiveness_against(*self) == Effectiveness::Weak)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effectiveness {
+    Weak,
+    Neutral,
+    Strong,
+}
+
+impl Effectiveness {
+    pub const fn multiplier(&self) -> f32 {
+        match self {
+            Effectiveness::Weak => 0.6,
+            Effectiveness::Neutral => 1.0,
+            Effectiveness::Strong => 1.5,
+        }
+    }
+
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Effectiveness::Weak => "não é muito efetivo",
+            Effectiveness::Neutral => "efetividade normal",
+            Effectiveness::Strong => "é super efetivo",
+        }
+    }
+
+    pub const fn emoji(&self) -> &'static str {
+        match self {
+            Effectiveness::Weak => "🔻",
+            Effectiveness::Neutral => "",
+            Effectiveness::Strong => "🔺",
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::{fmt::Display, ops::Add};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{effects::StatusEffectType, Emoji};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ItemType {
+    HealthPotion,
+    GreaterHealthPotion,
+    ManaPotion,
+    Antidote,
+    WoodenSword,
+    IronSword,
+    ApprenticeStaff,
+    ShadowDagger,
+    LeatherArmor,
+    IronArmor,
+    WisdomAmulet,
+    Wood,
+    IronOre,
+    WolfFang,
+    Slime,
+    AncientBark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ItemRarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl ItemRarity {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ItemRarity::Common => "Comum",
+            ItemRarity::Uncommon => "Incomum",
+            ItemRarity::Rare => "Raro",
+            ItemRarity::Epic => "Épico",
+            ItemRarity::Legendary => "Lendário",
+        }
+    }
+
+    pub const fn emoji(&self) -> &'static str {
+        match self {
+            ItemRarity::Common => "⚪",
+            ItemRarity::Uncommon => "🟢",
+            ItemRarity::Rare => "🔵",
+            ItemRarity::Epic => "🟣",
+            ItemRarity::Legendary => "🟠",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemEffect {
+    RestoreHealth(i32),
+    RestoreMana(i32),
+    CureStatus(StatusEffectType),
+}
+
+impl Display for ItemEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemEffect::RestoreHealth(amount) => {
+                write!(f, "Restaura **{}** {} de vida", amount, Emoji::Health)
+            }
+            ItemEffect::RestoreMana(amount) => {
+                write!(f, "Restaura **{}** {} de mana", amount, Emoji::Mana)
+            }
+            ItemEffect::CureStatus(effect) => {
+                write!(f, "Cura {} **{}**", effect.emoji(), effect.name())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+    Accessory,
+}
+
+impl EquipmentSlot {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            EquipmentSlot::Weapon => "Arma",
+            EquipmentSlot::Armor => "Armadura",
+            EquipmentSlot::Accessory => "Acessório",
+        }
+    }
+}
+
+/// Stats added to the character while the equipment is worn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EquipmentStats {
+    pub health: i32,
+    pub mana: i32,
+    pub strength: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+}
+
+impl EquipmentStats {
+    pub const NONE: EquipmentStats = EquipmentStats {
+        health: 0,
+        mana: 0,
+        strength: 0,
+        agility: 0,
+        intelligence: 0,
+    };
+}
+
+impl Add for EquipmentStats {
+    type Output = EquipmentStats;
+
+    fn add(self, other: EquipmentStats) -> EquipmentStats {
+        EquipmentStats {
+            health: self.health + other.health,
+            mana: self.mana + other.mana,
+            strength: self.strength + other.strength,
+            agility: self.agility + other.agility,
+            intelligence: self.intelligence + other.intelligence,
+        }
+    }
+}
+
+impl Display for EquipmentStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stats = [
+            (self.health, Emoji::Health, "Vida"),
+            (self.mana, Emoji::Mana, "Mana"),
+            (self.strength, Emoji::Strength, "Força"),
+            (self.agility, Emoji::Agility, "Agilidade"),
+            (self.intelligence, Emoji::Intelligence, "Inteligência"),
+        ];
+
+        let bonuses = stats
+            .iter()
+            .filter(|(value, _, _)| *value != 0)
+            .map(|(value, emoji, name)| format!("{} {:+} {}", emoji, value, name))
+            .collect::<Vec<String>>();
+
+        write!(f, "{}", bonuses.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Consumable { effects: &'static [ItemEffect] },
+    Equipment { slot: EquipmentSlot, stats: EquipmentStats },
+    /// Used for crafting and trading
+    Material,
+}
+
+impl ItemKind {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Consumable { .. } => "Consumível",
+            ItemKind::Equipment { .. } => "Equipamento",
+            ItemKind::Material => "Material",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Item {
+    pub item_type: ItemType,
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub description: &'static str,
+    pub rarity: ItemRarity,
+    /// Base price in gold
+    pub price: i32,
+    /// Maximum amount of this item a character can carry
+    pub max_stack: i32,
+    pub kind: ItemKind,
+}
+
+impl Item {
+    pub fn equipment(&self) -> Option<(EquipmentSlot, EquipmentStats)> {
+        match self.kind {
+            ItemKind::Equipment { slot, stats } => Some((slot, stats)),
+            _ => None,
+        }
+    }
+
+    pub fn display_effects(&self) -> String {
+        match self.kind {
+            ItemKind::Consumable { effects } => effects
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            ItemKind::Equipment { slot, stats } => format!("**{}**: {}", slot.name(), stats),
+            ItemKind::Material => "Usado para criar e trocar itens".into(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub const HEALTH_POTION: Item = Item {
+    item_type: ItemType::HealthPotion,
+    name: "Poção de Vida",
+    emoji: "🧪",
+    description: "Um frasco de líquido vermelho que fecha pequenas feridas.",
+    rarity: ItemRarity::Common,
+    price: 15,
+    max_stack: 20,
+    kind: ItemKind::Consumable { effects: &[ItemEffect::RestoreHealth(40)] },
+};
+
+pub const GREATER_HEALTH_POTION: Item = Item {
+    item_type: ItemType::GreaterHealthPotion,
+    name: "Poção de Vida Maior",
+    emoji: "⚗️",
+    description: "Uma poção concentrada, capaz de reerguer até o mais ferido dos aventureiros.",
+    rarity: ItemRarity::Uncommon,
+    price: 45,
+    max_stack: 10,
+    kind: ItemKind::Consumable { effects: &[ItemEffect::RestoreHealth(100)] },
+};
+
+pub const MANA_POTION: Item = Item {
+    item_type: ItemType::ManaPotion,
+    name: "Poção de Mana",
+    emoji: "🫙",
+    description: "Um frasco de líquido azulado que reacende a energia mística.",
+    rarity: ItemRarity::Common,
+    price: 15,
+    max_stack: 20,
+    kind: ItemKind::Consumable { effects: &[ItemEffect::RestoreMana(20)] },
+};
+
+pub const ANTIDOTE: Item = Item {
+    item_type: ItemType::Antidote,
+    name: "Antídoto",
+    emoji: "🌱",
+    description: "Ervas amargas que eliminam qualquer veneno do corpo.",
+    rarity: ItemRarity::Common,
+    price: 10,
+    max_stack: 20,
+    kind: ItemKind::Consumable { effects: &[ItemEffect::CureStatus(StatusEffectType::Poison)] },
+};
+
+pub const WOODEN_SWORD: Item = Item {
+    item_type: ItemType::WoodenSword,
+    name: "Espada de Madeira",
+    emoji: "🏏",
+    description: "Melhor do que lutar de mãos vazias.",
+    rarity: ItemRarity::Common,
+    price: 40,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Weapon,
+        stats: EquipmentStats { strength: 4, ..EquipmentStats::NONE },
+    },
+};
+
+pub const IRON_SWORD: Item = Item {
+    item_type: ItemType::IronSword,
+    name: "Espada de Ferro",
+    emoji: "⚔️",
+    description: "Uma lâmina firme, forjada pelos ferreiros das cidades.",
+    rarity: ItemRarity::Uncommon,
+    price: 150,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Weapon,
+        stats: EquipmentStats { strength: 10, ..EquipmentStats::NONE },
+    },
+};
+
+pub const APPRENTICE_STAFF: Item = Item {
+    item_type: ItemType::ApprenticeStaff,
+    name: "Cajado de Aprendiz",
+    emoji: "🪄",
+    description: "Um cajado simples, usado pelos magos em seus primeiros estudos.",
+    rarity: ItemRarity::Uncommon,
+    price: 150,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Weapon,
+        stats: EquipmentStats { mana: 10, intelligence: 8, ..EquipmentStats::NONE },
+    },
+};
+
+pub const SHADOW_DAGGER: Item = Item {
+    item_type: ItemType::ShadowDagger,
+    name: "Adaga Sombria",
+    emoji: "🗡️",
+    description: "Uma adaga leve e escura, quase invisível durante a noite.",
+    rarity: ItemRarity::Rare,
+    price: 300,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Weapon,
+        stats: EquipmentStats { strength: 6, agility: 6, ..EquipmentStats::NONE },
+    },
+};
+
+pub const LEATHER_ARMOR: Item = Item {
+    item_type: ItemType::LeatherArmor,
+    name: "Armadura de Couro",
+    emoji: "🦺",
+    description: "Leve e resistente o bastante para a maioria das viagens.",
+    rarity: ItemRarity::Common,
+    price: 60,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Armor,
+        stats: EquipmentStats { health: 20, agility: 1, ..EquipmentStats::NONE },
+    },
+};
+
+pub const IRON_ARMOR: Item = Item {
+    item_type: ItemType::IronArmor,
+    name: "Armadura de Ferro",
+    emoji: "🛡️",
+    description: "Protege muito bem, mas pesa nos ombros de quem a veste.",
+    rarity: ItemRarity::Uncommon,
+    price: 200,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Armor,
+        stats: EquipmentStats { health: 50, agility: -2, ..EquipmentStats::NONE },
+    },
+};
+
+pub const WISDOM_AMULET: Item = Item {
+    item_type: ItemType::WisdomAmulet,
+    name: "Amuleto da Sabedoria",
+    emoji: "📿",
+    description: "Dizem que foi abençoado pela própria Phoenix.",
+    rarity: ItemRarity::Rare,
+    price: 350,
+    max_stack: 5,
+    kind: ItemKind::Equipment {
+        slot: EquipmentSlot::Accessory,
+        stats: EquipmentStats { mana: 15, intelligence: 6, ..EquipmentStats::NONE },
+    },
+};
+
+pub const WOOD: Item = Item {
+    item_type: ItemType::Wood,
+    name: "Madeira",
+    emoji: "🪵",
+    description: "Toras de madeira comum.",
+    rarity: ItemRarity::Common,
+    price: 2,
+    max_stack: 99,
+    kind: ItemKind::Material,
+};
+
+pub const IRON_ORE: Item = Item {
+    item_type: ItemType::IronOre,
+    name: "Minério de Ferro",
+    emoji: "🪨",
+    description: "Um pedaço de rocha com veios de ferro.",
+    rarity: ItemRarity::Common,
+    price: 5,
+    max_stack: 99,
+    kind: ItemKind::Material,
+};
+
+pub const WOLF_FANG: Item = Item {
+    item_type: ItemType::WolfFang,
+    name: "Presa de Lobo",
+    emoji: "🦷",
+    description: "Uma presa afiada, arrancada de um lobo derrotado.",
+    rarity: ItemRarity::Uncommon,
+    price: 8,
+    max_stack: 99,
+    kind: ItemKind::Material,
+};
+
+pub const SLIME: Item = Item {
+    item_type: ItemType::Slime,
+    name: "Gosma",
+    emoji: "🟢",
+    description: "Uma gosma pegajosa que sobra dos blobs.",
+    rarity: ItemRarity::Common,
+    price: 3,
+    max_stack: 99,
+    kind: ItemKind::Material,
+};
+
+pub const ANCIENT_BARK: Item = Item {
+    item_type: ItemType::AncientBark,
+    name: "Casca Ancestral",
+    emoji: "🌳",
+    description: "Um pedaço da casca de uma árvore muito antiga, ainda pulsando com vida.",
+    rarity: ItemRarity::Rare,
+    price: 40,
+    max_stack: 99,
+    kind: ItemKind::Material,
+};
+
+pub const ALL_ITEMS: [Item; 16] = [
+    HEALTH_POTION,
+    GREATER_HEALTH_POTION,
+    MANA_POTION,
+    ANTIDOTE,
+    WOODEN_SWORD,
+    IRON_SWORD,
+    APPRENTICE_STAFF,
+    SHADOW_DAGGER,
+    LEATHER_ARMOR,
+    IRON_ARMOR,
+    WISDOM_AMULET,
+    WOOD,
+    IRON_ORE,
+    WOLF_FANG,
+    SLIME,
+    ANCIENT_BARK,
+];
+
+pub const ALL_EQUIPMENT_SLOTS: [EquipmentSlot; 3] =
+    [EquipmentSlot::Weapon, EquipmentSlot::Armor, EquipmentSlot::Accessory];
+
+pub fn get_item_by_type(item_type: ItemType) -> Option<Item> {
+    ALL_ITEMS.iter().find(|i| i.item_type == item_type).copied()
+}
@@ -1,6 +1,15 @@
 pub mod anomalies;
+pub mod bosses;
 pub mod classes;
+pub mod crafting;
+pub mod effects;
+pub mod elements;
+pub mod items;
 pub mod regions;
+pub mod passives;
+pub mod shop;
+pub mod skill_trees;
+pub mod skills;
 mod common;
 mod emoji;
 
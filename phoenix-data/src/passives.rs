@@ -0,0 +1,59 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Effects that are always active during a battle, without spending the fighter's turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PassiveEffect {
+    /// Percentage of the damage dealt that heals the fighter
+    Lifesteal(i32),
+    /// Percentage of the damage received that is dealt back to the attacker
+    Thorns(i32),
+    /// Extra percentage of damage dealt by critical hits
+    CriticalDamage(i32),
+    /// Percentage of damage received that is ignored
+    DamageReduction(i32),
+    /// Mana restored at the end of each of the fighter's turns
+    ManaRegeneration(i32),
+    /// The first attack or damaging skill of the battle is always a critical hit
+    FirstStrikeCritical,
+}
+
+impl Display for PassiveEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PassiveEffect::Lifesteal(percentage) => {
+                write!(f, "cura {}% do dano causado", percentage)
+            }
+            PassiveEffect::Thorns(percentage) => {
+                write!(f, "devolve {}% do dano recebido", percentage)
+            }
+            PassiveEffect::CriticalDamage(percentage) => {
+                write!(f, "+{}% de dano em acertos críticos", percentage)
+            }
+            PassiveEffect::DamageReduction(percentage) => {
+                write!(f, "-{}% de dano recebido", percentage)
+            }
+            PassiveEffect::ManaRegeneration(amount) => {
+                write!(f, "recupera {} de mana a cada rodada", amount)
+            }
+            PassiveEffect::FirstStrikeCritical => {
+                write!(f, "o primeiro golpe da batalha é sempre crítico")
+            }
+        }
+    }
+}
+
+/// A named passive that every member of a class has from the start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Passive {
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub effect: PassiveEffect,
+}
+
+impl Display for Passive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} **{}**: {}", self.emoji, self.name, self.effect)
+    }
+}
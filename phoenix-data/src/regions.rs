@@ -1,7 +1,7 @@
 use rand::Rng;
 use serde::{Serialize, Deserialize};
 
-use crate::{Probability, common};
+use crate::{elements::Element, Probability, common};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RegionType {
@@ -46,13 +46,24 @@ impl RegionType {
         }
     }
 
-    pub fn generate_specific_name(&self) -> String {
-        let rng = &mut rand::thread_rng();
+    /// Anomalies of this element are at home in the region and fight better there
+    pub const fn element(&self) -> Element {
+        match self {
+            RegionType::City => Element::Light,
+            RegionType::Forest => Element::Earth,
+            RegionType::Swamp => Element::Water,
+            RegionType::Grassland => Element::Nature,
+        }
+    }
+
+    pub fn generate_specific_name(&self, rng: &mut impl Rng) -> String {
         let mut title: Option<String> = None;
         if self == &RegionType::City || rng.gen_bool(0.3) {
+            let syllables = rng.gen_range(1..=4);
             title = Some(
                 common::invent_word(
-                    rand::thread_rng().gen_range(1..=4)
+                    rng,
+                    syllables
                 ).unwrap_or("UNKNOWN".to_string())
             );
         } 
@@ -75,7 +86,7 @@ impl RegionType {
             _ => "o"
         };
 
-        common::generate_name(&string, first_suffix, second_suffix, last_suffix)
+        common::generate_name(rng, &string, first_suffix, second_suffix, last_suffix)
             .unwrap_or(string)
             .replace("$", pronoun_letter)
     }
@@ -87,4 +98,37 @@ impl RegionType {
             _ => Probability::new(50)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{anomalies::ANOMALIES, bosses::get_region_boss};
+
+    #[test]
+    fn regions_are_home_to_their_anomalies_and_boss() {
+        for region in REGIONS {
+            let anomalies = ANOMALIES
+                .iter()
+                .filter(|a| a.valid_regions.contains(&region))
+                .collect::<Vec<_>>();
+
+            if !anomalies.is_empty() {
+                assert!(
+                    anomalies.iter().any(|a| a.element == region.element()),
+                    "No anomaly of {} is at home there",
+                    region.name()
+                );
+            }
+
+            if let Some(boss) = get_region_boss(region) {
+                assert_eq!(
+                    boss.anomaly.element,
+                    region.element(),
+                    "The boss of {} is not at home there",
+                    region.name()
+                );
+            }
+        }
+    }
+}
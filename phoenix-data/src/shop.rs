@@ -0,0 +1,58 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    items::{Item, ItemRarity, ItemType, ALL_ITEMS},
+    Probability,
+};
+
+const STOCK_SIZE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShopOffer {
+    pub item: Item,
+    pub price: i32,
+}
+
+const fn shop_chance(rarity: ItemRarity) -> Probability {
+    match rarity {
+        ItemRarity::Common => Probability::new(100),
+        ItemRarity::Uncommon => Probability::new(60),
+        ItemRarity::Rare => Probability::new(25),
+        ItemRarity::Epic => Probability::new(8),
+        ItemRarity::Legendary => Probability::new(2),
+    }
+}
+
+// FNV-1a, so the seed doesn't change between Rust versions like the std hasher might
+fn hash_name(name: &str) -> u64 {
+    name.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The same city always sells the same items for the same prices during a day
+pub fn generate_city_stock(city_name: &str, day: i64) -> Vec<ShopOffer> {
+    let rng = &mut StdRng::seed_from_u64(hash_name(city_name) ^ day as u64);
+
+    let mut items: Vec<Item> = ALL_ITEMS
+        .iter()
+        .copied()
+        .filter(|i| i.item_type != ItemType::HealthPotion)
+        .filter(|i| shop_chance(i.rarity).generate_random_bool(rng))
+        .collect();
+
+    items.shuffle(rng);
+    items.truncate(STOCK_SIZE - 1);
+
+    // Every city has health potions for the road
+    items.extend(ALL_ITEMS.iter().copied().find(|i| i.item_type == ItemType::HealthPotion));
+    items.sort_by_key(|i| i.item_type);
+
+    items
+        .into_iter()
+        .map(|item| ShopOffer {
+            item,
+            price: ((item.price as f32 * rng.gen_range(0.85..1.25)).round() as i32).max(1),
+        })
+        .collect()
+}
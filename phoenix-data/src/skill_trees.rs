@@ -0,0 +1,448 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    classes::ClassType,
+    items::EquipmentStats,
+    passives::PassiveEffect,
+    skills::{get_skill_by_type, SkillType},
+};
+
+/// Level in which a player chooses the subclass that evolves their class
+pub const SUBCLASS_LEVEL: i32 = 10;
+
+/// Players earn a skill point every time they level up
+pub const fn skill_points_for_level(level: i32) -> i32 {
+    if level > 1 {
+        level - 1
+    } else {
+        0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SubclassType {
+    Paladin,
+    Berserker,
+    Archmage,
+    Warlock,
+    Shade,
+    Duelist,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subclass {
+    pub subclass_type: SubclassType,
+    pub class: ClassType,
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub description: &'static str,
+    /// Skill learned as soon as the subclass is chosen
+    pub skill: SkillType,
+}
+
+pub const PALADIN: Subclass = Subclass {
+    subclass_type: SubclassType::Paladin,
+    class: ClassType::Knight,
+    name: "Paladino",
+    emoji: "🌟",
+    description: "Cavaleiros devotos que carregam a chama de Phoenix, protegendo e curando quem luta ao seu lado.",
+    skill: SkillType::HolyLight,
+};
+
+pub const BERSERKER: Subclass = Subclass {
+    subclass_type: SubclassType::Berserker,
+    class: ClassType::Knight,
+    name: "Berserker",
+    emoji: "🪓",
+    description: "Cavaleiros que abandonaram o escudo e se entregaram à fúria, se alimentando do sangue de seus inimigos.",
+    skill: SkillType::Rampage,
+};
+
+pub const ARCHMAGE: Subclass = Subclass {
+    subclass_type: SubclassType::Archmage,
+    class: ClassType::Mage,
+    name: "Arquimago",
+    emoji: "☄️",
+    description: "Mestres das artes arcanas, capazes de trazer o fogo do céu sobre as anomalias.",
+    skill: SkillType::Meteor,
+};
+
+pub const WARLOCK: Subclass = Subclass {
+    subclass_type: SubclassType::Warlock,
+    class: ClassType::Mage,
+    name: "Bruxo",
+    emoji: "🕯️",
+    description: "Magos que estudaram as próprias anomalias e aprenderam a drenar a vida e a mana de seus alvos.",
+    skill: SkillType::Curse,
+};
+
+pub const SHADE: Subclass = Subclass {
+    subclass_type: SubclassType::Shade,
+    class: ClassType::Assassin,
+    name: "Sombra",
+    emoji: "🥷",
+    description: "Assassinos que nunca são vistos, acabando com a luta em um único golpe certeiro.",
+    skill: SkillType::Ambush,
+};
+
+pub const DUELIST: Subclass = Subclass {
+    subclass_type: SubclassType::Duelist,
+    class: ClassType::Assassin,
+    name: "Duelista",
+    emoji: "🤺",
+    description: "Assassinos que preferem o combate direto, respondendo cada golpe com uma lâmina ainda mais rápida.",
+    skill: SkillType::Lunge,
+};
+
+pub const ALL_SUBCLASSES: [Subclass; 6] = [PALADIN, BERSERKER, ARCHMAGE, WARLOCK, SHADE, DUELIST];
+
+pub fn get_subclass_by_type(subclass_type: SubclassType) -> Option<Subclass> {
+    ALL_SUBCLASSES
+        .iter()
+        .find(|s| s.subclass_type == subclass_type)
+        .copied()
+}
+
+pub fn get_subclasses_of_class(class: ClassType) -> Vec<Subclass> {
+    ALL_SUBCLASSES
+        .iter()
+        .filter(|s| s.class == class)
+        .copied()
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SkillNodeType {
+    KnightVitality,
+    KnightShieldBash,
+    KnightBulwark,
+    PaladinDevotion,
+    PaladinRetribution,
+    BerserkerBloodlust,
+    BerserkerBrutality,
+    MageFocus,
+    MageArcaneBolt,
+    MageManaFlow,
+    ArchmageMastery,
+    ArchmageOverload,
+    WarlockSoulSiphon,
+    WarlockDarkPact,
+    AssassinReflexes,
+    AssassinVenomBlade,
+    AssassinPrecision,
+    ShadeEvasion,
+    ShadeLethality,
+    DuelistFootwork,
+    DuelistRiposte,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillNodeEffect {
+    Stats(EquipmentStats),
+    Skill(SkillType),
+    Passive(PassiveEffect),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillNode {
+    pub node_type: SkillNodeType,
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub class: ClassType,
+    /// Nodes of a subclass can only be unlocked after choosing it
+    pub subclass: Option<SubclassType>,
+    pub required_level: i32,
+    /// Node that must be unlocked before this one
+    pub requires: Option<SkillNodeType>,
+    pub cost: i32,
+    pub effects: &'static [SkillNodeEffect],
+}
+
+const fn stats(
+    health: i32,
+    mana: i32,
+    strength: i32,
+    agility: i32,
+    intelligence: i32,
+) -> SkillNodeEffect {
+    SkillNodeEffect::Stats(EquipmentStats {
+        health,
+        mana,
+        strength,
+        agility,
+        intelligence,
+    })
+}
+
+pub const ALL_SKILL_NODES: [SkillNode; 21] = [
+    SkillNode {
+        node_type: SkillNodeType::KnightVitality,
+        name: "Vigor",
+        emoji: "❤️",
+        class: ClassType::Knight,
+        subclass: None,
+        required_level: 2,
+        requires: None,
+        cost: 1,
+        effects: &[stats(25, 0, 0, 0, 0)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::KnightShieldBash,
+        name: "Golpe de Escudo",
+        emoji: "🛡️",
+        class: ClassType::Knight,
+        subclass: None,
+        required_level: 4,
+        requires: Some(SkillNodeType::KnightVitality),
+        cost: 1,
+        effects: &[SkillNodeEffect::Skill(SkillType::ShieldBash)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::KnightBulwark,
+        name: "Baluarte",
+        emoji: "🏰",
+        class: ClassType::Knight,
+        subclass: None,
+        required_level: 7,
+        requires: Some(SkillNodeType::KnightShieldBash),
+        cost: 2,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::DamageReduction(10))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::PaladinDevotion,
+        name: "Devoção",
+        emoji: "🙏",
+        class: ClassType::Knight,
+        subclass: Some(SubclassType::Paladin),
+        required_level: SUBCLASS_LEVEL,
+        requires: None,
+        cost: 2,
+        effects: &[stats(0, 20, 0, 0, 8)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::PaladinRetribution,
+        name: "Retribuição",
+        emoji: "⚖️",
+        class: ClassType::Knight,
+        subclass: Some(SubclassType::Paladin),
+        required_level: 13,
+        requires: Some(SkillNodeType::PaladinDevotion),
+        cost: 3,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::Thorns(20))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::BerserkerBloodlust,
+        name: "Sede de Sangue",
+        emoji: "🩸",
+        class: ClassType::Knight,
+        subclass: Some(SubclassType::Berserker),
+        required_level: SUBCLASS_LEVEL,
+        requires: None,
+        cost: 2,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::Lifesteal(15))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::BerserkerBrutality,
+        name: "Brutalidade",
+        emoji: "💢",
+        class: ClassType::Knight,
+        subclass: Some(SubclassType::Berserker),
+        required_level: 13,
+        requires: Some(SkillNodeType::BerserkerBloodlust),
+        cost: 3,
+        effects: &[
+            stats(0, 0, 6, 0, 0),
+            SkillNodeEffect::Passive(PassiveEffect::CriticalDamage(30)),
+        ],
+    },
+    SkillNode {
+        node_type: SkillNodeType::MageFocus,
+        name: "Foco",
+        emoji: "🧘",
+        class: ClassType::Mage,
+        subclass: None,
+        required_level: 2,
+        requires: None,
+        cost: 1,
+        effects: &[stats(0, 15, 0, 0, 3)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::MageArcaneBolt,
+        name: "Raio Arcano",
+        emoji: "⚡",
+        class: ClassType::Mage,
+        subclass: None,
+        required_level: 4,
+        requires: Some(SkillNodeType::MageFocus),
+        cost: 1,
+        effects: &[SkillNodeEffect::Skill(SkillType::ArcaneBolt)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::MageManaFlow,
+        name: "Fluxo de Mana",
+        emoji: "💧",
+        class: ClassType::Mage,
+        subclass: None,
+        required_level: 7,
+        requires: Some(SkillNodeType::MageArcaneBolt),
+        cost: 2,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::ManaRegeneration(3))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::ArchmageMastery,
+        name: "Maestria Arcana",
+        emoji: "📖",
+        class: ClassType::Mage,
+        subclass: Some(SubclassType::Archmage),
+        required_level: SUBCLASS_LEVEL,
+        requires: None,
+        cost: 2,
+        effects: &[stats(0, 20, 0, 0, 8)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::ArchmageOverload,
+        name: "Sobrecarga",
+        emoji: "💥",
+        class: ClassType::Mage,
+        subclass: Some(SubclassType::Archmage),
+        required_level: 13,
+        requires: Some(SkillNodeType::ArchmageMastery),
+        cost: 3,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::CriticalDamage(40))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::WarlockSoulSiphon,
+        name: "Sifão de Almas",
+        emoji: "👻",
+        class: ClassType::Mage,
+        subclass: Some(SubclassType::Warlock),
+        required_level: SUBCLASS_LEVEL,
+        requires: None,
+        cost: 2,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::Lifesteal(12))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::WarlockDarkPact,
+        name: "Pacto Sombrio",
+        emoji: "📜",
+        class: ClassType::Mage,
+        subclass: Some(SubclassType::Warlock),
+        required_level: 13,
+        requires: Some(SkillNodeType::WarlockSoulSiphon),
+        cost: 3,
+        effects: &[
+            stats(30, 0, 0, 0, 5),
+            SkillNodeEffect::Passive(PassiveEffect::ManaRegeneration(3)),
+        ],
+    },
+    SkillNode {
+        node_type: SkillNodeType::AssassinReflexes,
+        name: "Reflexos",
+        emoji: "🪶",
+        class: ClassType::Assassin,
+        subclass: None,
+        required_level: 2,
+        requires: None,
+        cost: 1,
+        effects: &[stats(0, 0, 0, 4, 0)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::AssassinVenomBlade,
+        name: "Lâmina Venenosa",
+        emoji: "🐍",
+        class: ClassType::Assassin,
+        subclass: None,
+        required_level: 4,
+        requires: Some(SkillNodeType::AssassinReflexes),
+        cost: 1,
+        effects: &[SkillNodeEffect::Skill(SkillType::VenomBlade)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::AssassinPrecision,
+        name: "Precisão",
+        emoji: "🎯",
+        class: ClassType::Assassin,
+        subclass: None,
+        required_level: 7,
+        requires: Some(SkillNodeType::AssassinVenomBlade),
+        cost: 2,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::CriticalDamage(25))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::ShadeEvasion,
+        name: "Evasão",
+        emoji: "🌫️",
+        class: ClassType::Assassin,
+        subclass: Some(SubclassType::Shade),
+        required_level: SUBCLASS_LEVEL,
+        requires: None,
+        cost: 2,
+        effects: &[stats(0, 0, 0, 6, 0)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::ShadeLethality,
+        name: "Letalidade",
+        emoji: "💀",
+        class: ClassType::Assassin,
+        subclass: Some(SubclassType::Shade),
+        required_level: 13,
+        requires: Some(SkillNodeType::ShadeEvasion),
+        cost: 3,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::CriticalDamage(40))],
+    },
+    SkillNode {
+        node_type: SkillNodeType::DuelistFootwork,
+        name: "Jogo de Pés",
+        emoji: "👣",
+        class: ClassType::Assassin,
+        subclass: Some(SubclassType::Duelist),
+        required_level: SUBCLASS_LEVEL,
+        requires: None,
+        cost: 2,
+        effects: &[stats(0, 0, 4, 4, 0)],
+    },
+    SkillNode {
+        node_type: SkillNodeType::DuelistRiposte,
+        name: "Contra-ataque",
+        emoji: "↩️",
+        class: ClassType::Assassin,
+        subclass: Some(SubclassType::Duelist),
+        required_level: 13,
+        requires: Some(SkillNodeType::DuelistFootwork),
+        cost: 3,
+        effects: &[SkillNodeEffect::Passive(PassiveEffect::Thorns(25))],
+    },
+];
+
+impl SkillNode {
+    pub fn display_effects(&self) -> String {
+        self.effects
+            .iter()
+            .map(|effect| match effect {
+                SkillNodeEffect::Stats(stats) => stats.to_string(),
+                SkillNodeEffect::Skill(skill) => get_skill_by_type(*skill)
+                    .map_or("Nova habilidade".into(), |s| {
+                        format!("Aprende {} **{}**", s.emoji, s.name)
+                    }),
+                SkillNodeEffect::Passive(passive) => format!("Passiva: {}", passive),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+pub fn get_skill_node_by_type(node_type: SkillNodeType) -> Option<SkillNode> {
+    ALL_SKILL_NODES
+        .iter()
+        .find(|n| n.node_type == node_type)
+        .copied()
+}
+
+/// Nodes of the class tree, including the ones of every subclass
+pub fn get_skill_tree(class: ClassType) -> Vec<SkillNode> {
+    ALL_SKILL_NODES
+        .iter()
+        .filter(|n| n.class == class)
+        .copied()
+        .collect()
+}
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    effects::{StatusEffectType, StatusInfliction},
+    elements::Element,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkillType {
+    HeavyStrike,
+    SecondWind,
+    Fireball,
+    Heal,
+    ArcaneDrain,
+    ShadowStrike,
+    EssenceDrain,
+    Regrowth,
+    FeralBite,
+    AcidSpit,
+    Earthquake,
+    ToxicTide,
+    Rampage,
+    ShieldBash,
+    HolyLight,
+    ArcaneBolt,
+    Meteor,
+    Curse,
+    VenomBlade,
+    Ambush,
+    Lunge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SkillEffect {
+    /// Damage dealt to the target, scaled by the caster's strength and intelligence
+    Damage { strength: f32, intelligence: f32 },
+    /// Health restored to the caster, scaled by the caster's intelligence
+    Heal { base: i32, intelligence: f32 },
+    /// Mana removed from the target
+    DrainMana(i32),
+    /// Status effect that may be inflicted on the target
+    InflictStatus(StatusInfliction),
+    /// Status effect that may be applied to the caster
+    GainStatus(StatusInfliction),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Skill {
+    pub skill_type: SkillType,
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub description: &'static str,
+    pub mana_cost: i32,
+    /// Element of the damage dealt by the skill
+    pub element: Element,
+    pub effects: &'static [SkillEffect],
+}
+
+impl Skill {
+    pub fn deals_damage(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|e| matches!(e, SkillEffect::Damage { .. }))
+    }
+
+    pub fn heals(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|e| matches!(e, SkillEffect::Heal { .. }))
+    }
+}
+
+pub const HEAVY_STRIKE: Skill = Skill {
+    skill_type: SkillType::HeavyStrike,
+    name: "Golpe Pesado",
+    emoji: "🔨",
+    description: "Um golpe lento e brutal que causa muito dano e pode atordoar o alvo.",
+    mana_cost: 8,
+    element: Element::Neutral,
+    effects: &[
+        SkillEffect::Damage { strength: 1.6, intelligence: 0.0 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Stun, 20, 1, 0)),
+    ],
+};
+
+pub const SECOND_WIND: Skill = Skill {
+    skill_type: SkillType::SecondWind,
+    name: "Segundo Fôlego",
+    emoji: "❤️‍🩹",
+    description: "Recupera o fôlego no meio da batalha e restaura um pouco de vida.",
+    mana_cost: 10,
+    element: Element::Neutral,
+    effects: &[SkillEffect::Heal { base: 15, intelligence: 1.0 }],
+};
+
+pub const FIREBALL: Skill = Skill {
+    skill_type: SkillType::Fireball,
+    name: "Bola de Fogo",
+    emoji: "🔥",
+    description: "Lança uma bola de fogo que causa dano baseado na inteligência e pode queimar o alvo.",
+    mana_cost: 12,
+    element: Element::Fire,
+    effects: &[
+        SkillEffect::Damage { strength: 0.0, intelligence: 2.2 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Burn, 40, 2, 8)),
+    ],
+};
+
+pub const HEAL: Skill = Skill {
+    skill_type: SkillType::Heal,
+    name: "Cura",
+    emoji: "✨",
+    description: "Restaura vida baseada na inteligência e regenera um pouco a cada rodada.",
+    mana_cost: 15,
+    element: Element::Light,
+    effects: &[
+        SkillEffect::Heal { base: 10, intelligence: 1.5 },
+        SkillEffect::GainStatus(StatusInfliction::new(StatusEffectType::Regeneration, 100, 3, 5)),
+    ],
+};
+
+pub const ARCANE_DRAIN: Skill = Skill {
+    skill_type: SkillType::ArcaneDrain,
+    name: "Dreno Arcano",
+    emoji: "🌀",
+    description: "Causa um pouco de dano e drena a mana do alvo.",
+    mana_cost: 6,
+    element: Element::Neutral,
+    effects: &[
+        SkillEffect::Damage { strength: 0.0, intelligence: 0.8 },
+        SkillEffect::DrainMana(10),
+    ],
+};
+
+pub const SHADOW_STRIKE: Skill = Skill {
+    skill_type: SkillType::ShadowStrike,
+    name: "Golpe Sombrio",
+    emoji: "🌑",
+    description: "Um ataque rápido pelas sombras que usa força e inteligência e pode envenenar o alvo.",
+    mana_cost: 8,
+    element: Element::Shadow,
+    effects: &[
+        SkillEffect::Damage { strength: 1.2, intelligence: 0.6 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Poison, 35, 3, 4)),
+    ],
+};
+
+pub const ESSENCE_DRAIN: Skill = Skill {
+    skill_type: SkillType::EssenceDrain,
+    name: "Drenar Essência",
+    emoji: "🩸",
+    description: "Fere o alvo e drena um pouco de sua mana.",
+    mana_cost: 6,
+    element: Element::Shadow,
+    effects: &[
+        SkillEffect::Damage { strength: 0.6, intelligence: 0.0 },
+        SkillEffect::DrainMana(8),
+    ],
+};
+
+pub const REGROWTH: Skill = Skill {
+    skill_type: SkillType::Regrowth,
+    name: "Rebrotar",
+    emoji: "🌿",
+    description: "Regenera a casca danificada, restaurando vida ao longo das rodadas.",
+    mana_cost: 8,
+    element: Element::Nature,
+    effects: &[
+        SkillEffect::Heal { base: 10, intelligence: 1.0 },
+        SkillEffect::GainStatus(StatusInfliction::new(StatusEffectType::Regeneration, 100, 2, 4)),
+    ],
+};
+
+pub const FERAL_BITE: Skill = Skill {
+    skill_type: SkillType::FeralBite,
+    name: "Mordida Feroz",
+    emoji: "🐺",
+    description: "Uma mordida selvagem que causa bastante dano.",
+    mana_cost: 1,
+    element: Element::Nature,
+    effects: &[SkillEffect::Damage { strength: 1.4, intelligence: 0.0 }],
+};
+
+pub const ACID_SPIT: Skill = Skill {
+    skill_type: SkillType::AcidSpit,
+    name: "Cuspe Ácido",
+    emoji: "🧪",
+    description: "Cospe ácido no alvo, podendo envenená-lo.",
+    mana_cost: 5,
+    element: Element::Water,
+    effects: &[
+        SkillEffect::Damage { strength: 0.5, intelligence: 1.0 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Poison, 50, 2, 5)),
+    ],
+};
+
+pub const EARTHQUAKE: Skill = Skill {
+    skill_type: SkillType::Earthquake,
+    name: "Terremoto",
+    emoji: "🌋",
+    description: "Faz o chão tremer, causando dano e podendo atordoar o alvo.",
+    mana_cost: 12,
+    element: Element::Earth,
+    effects: &[
+        SkillEffect::Damage { strength: 1.3, intelligence: 0.0 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Stun, 40, 1, 0)),
+    ],
+};
+
+pub const TOXIC_TIDE: Skill = Skill {
+    skill_type: SkillType::ToxicTide,
+    name: "Maré Tóxica",
+    emoji: "🌊",
+    description: "Uma onda de lama venenosa que quase sempre envenena o alvo.",
+    mana_cost: 12,
+    element: Element::Water,
+    effects: &[
+        SkillEffect::Damage { strength: 0.4, intelligence: 1.6 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Poison, 80, 3, 6)),
+    ],
+};
+
+pub const RAMPAGE: Skill = Skill {
+    skill_type: SkillType::Rampage,
+    name: "Fúria Selvagem",
+    emoji: "💢",
+    description: "Uma sequência descontrolada de golpes que causa muito dano.",
+    mana_cost: 15,
+    element: Element::Neutral,
+    effects: &[SkillEffect::Damage { strength: 2.2, intelligence: 0.0 }],
+};
+
+pub const SHIELD_BASH: Skill = Skill {
+    skill_type: SkillType::ShieldBash,
+    name: "Golpe de Escudo",
+    emoji: "🛡️",
+    description: "Acerta o alvo com o escudo, com uma boa chance de atordoá-lo.",
+    mana_cost: 6,
+    element: Element::Neutral,
+    effects: &[
+        SkillEffect::Damage { strength: 0.9, intelligence: 0.0 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Stun, 45, 1, 0)),
+    ],
+};
+
+pub const HOLY_LIGHT: Skill = Skill {
+    skill_type: SkillType::HolyLight,
+    name: "Luz Sagrada",
+    emoji: "🌟",
+    description: "Invoca a chama de Phoenix para ferir o alvo e curar as próprias feridas.",
+    mana_cost: 14,
+    element: Element::Light,
+    effects: &[
+        SkillEffect::Damage { strength: 0.8, intelligence: 0.8 },
+        SkillEffect::Heal { base: 15, intelligence: 1.2 },
+    ],
+};
+
+pub const ARCANE_BOLT: Skill = Skill {
+    skill_type: SkillType::ArcaneBolt,
+    name: "Raio Arcano",
+    emoji: "⚡",
+    description: "Um disparo rápido de energia arcana, barato e preciso.",
+    mana_cost: 5,
+    element: Element::Neutral,
+    effects: &[SkillEffect::Damage { strength: 0.0, intelligence: 1.4 }],
+};
+
+pub const METEOR: Skill = Skill {
+    skill_type: SkillType::Meteor,
+    name: "Meteoro",
+    emoji: "☄️",
+    description: "Faz um meteoro cair sobre o alvo, causando um dano enorme e queimando-o.",
+    mana_cost: 25,
+    element: Element::Fire,
+    effects: &[
+        SkillEffect::Damage { strength: 0.0, intelligence: 3.0 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Burn, 60, 2, 8)),
+    ],
+};
+
+pub const CURSE: Skill = Skill {
+    skill_type: SkillType::Curse,
+    name: "Maldição",
+    emoji: "🕯️",
+    description: "Amaldiçoa o alvo, envenenando-o e drenando sua mana.",
+    mana_cost: 10,
+    element: Element::Shadow,
+    effects: &[
+        SkillEffect::Damage { strength: 0.0, intelligence: 1.0 },
+        SkillEffect::DrainMana(10),
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Poison, 70, 3, 5)),
+    ],
+};
+
+pub const VENOM_BLADE: Skill = Skill {
+    skill_type: SkillType::VenomBlade,
+    name: "Lâmina Venenosa",
+    emoji: "🐍",
+    description: "Um corte com uma lâmina envenenada que quase sempre envenena o alvo.",
+    mana_cost: 7,
+    element: Element::Nature,
+    effects: &[
+        SkillEffect::Damage { strength: 0.8, intelligence: 0.3 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Poison, 80, 3, 5)),
+    ],
+};
+
+pub const AMBUSH: Skill = Skill {
+    skill_type: SkillType::Ambush,
+    name: "Emboscada",
+    emoji: "🥷",
+    description: "Ataca das sombras com um golpe brutal que pode atordoar o alvo.",
+    mana_cost: 14,
+    element: Element::Shadow,
+    effects: &[
+        SkillEffect::Damage { strength: 1.8, intelligence: 0.4 },
+        SkillEffect::InflictStatus(StatusInfliction::new(StatusEffectType::Stun, 30, 1, 0)),
+    ],
+};
+
+pub const LUNGE: Skill = Skill {
+    skill_type: SkillType::Lunge,
+    name: "Estocada",
+    emoji: "🤺",
+    description: "Uma estocada precisa e rápida que causa bastante dano.",
+    mana_cost: 9,
+    element: Element::Neutral,
+    effects: &[SkillEffect::Damage { strength: 1.7, intelligence: 0.0 }],
+};
+
+pub const ALL_SKILLS: [Skill; 21] = [
+    HEAVY_STRIKE,
+    SECOND_WIND,
+    FIREBALL,
+    HEAL,
+    ARCANE_DRAIN,
+    SHADOW_STRIKE,
+    ESSENCE_DRAIN,
+    REGROWTH,
+    FERAL_BITE,
+    ACID_SPIT,
+    EARTHQUAKE,
+    TOXIC_TIDE,
+    RAMPAGE,
+    SHIELD_BASH,
+    HOLY_LIGHT,
+    ARCANE_BOLT,
+    METEOR,
+    CURSE,
+    VENOM_BLADE,
+    AMBUSH,
+    LUNGE,
+];
+
+pub fn get_skill_by_name(name: &str) -> Option<Skill> {
+    ALL_SKILLS.iter().find(|s| s.name == name).copied()
+}
+
+pub fn get_skill_by_type(skill_type: SkillType) -> Option<Skill> {
+    ALL_SKILLS
+        .iter()
+        .find(|s| s.skill_type == skill_type)
+        .copied()
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use data::{
+    anomalies::{AnomalyType, AnomalyVariant},
+    elements::Element,
+    items::ItemType,
+    passives::PassiveEffect,
+    skills::SkillType,
+    Stat,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattleLogParticipant {
+    pub user_id: Option<String>,
+    pub name: String,
+    pub team: i32,
+    pub image: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattleLogAnomaly {
+    pub anomaly_type: AnomalyType,
+    pub variant: Option<AnomalyVariant>,
+    pub level: i32,
+    pub xp: i32,
+    pub gold: i32,
+    pub at_home: bool,
+}
+
+/// A fighter as it was before the first round of the battle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattleLogFighter {
+    pub name: String,
+    pub user_id: Option<String>,
+    pub image: Option<String>,
+    pub anomaly: Option<BattleLogAnomaly>,
+    pub team: i32,
+    pub health: Stat,
+    pub mana: Stat,
+    pub strength: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+    pub element: Element,
+    pub skills: Vec<SkillType>,
+    pub passives: Vec<PassiveEffect>,
+    pub items: BTreeMap<ItemType, i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleLogAction {
+    Attack,
+    Defend,
+    Flee,
+    Skill(SkillType),
+    UseItem(ItemType),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattleLogRound {
+    pub fighter: i32,
+    pub target: Option<i32>,
+    pub action: Option<BattleLogAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BattleLog {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub participants: Vec<BattleLogParticipant>,
+    pub fighters: Vec<BattleLogFighter>,
+    // BSON has no unsigned 64 bits integer, so the seed is kept as a string
+    pub seed: String,
+    pub rounds: Vec<BattleLogRound>,
+    pub winning_team: Option<i32>,
+    pub fled_fighter: Option<String>,
+    pub created_at: i64,
+}
+
+impl BattleLog {
+    pub fn new(
+        participants: Vec<BattleLogParticipant>,
+        fighters: Vec<BattleLogFighter>,
+        seed: u64,
+        rounds: Vec<BattleLogRound>,
+        winning_team: Option<i32>,
+        fled_fighter: Option<String>,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            participants,
+            fighters,
+            seed: seed.to_string(),
+            rounds,
+            winning_team,
+            fled_fighter,
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn participant(&self, user_id: &String) -> Option<&BattleLogParticipant> {
+        self.participants
+            .iter()
+            .find(|p| p.user_id.as_ref() == Some(user_id))
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed.parse().ok()
+    }
+
+    pub fn winners(&self) -> Vec<&BattleLogParticipant> {
+        self.participants
+            .iter()
+            .filter(|p| Some(p.team) == self.winning_team)
+            .collect()
+    }
+}
@@ -1,15 +1,29 @@
+pub mod battle_log;
 pub mod cooldown;
+pub mod market;
+pub mod trade;
 pub mod user_model;
 
+use battle_log::BattleLog;
+use chrono::Utc;
 use cooldown::{CooldownData, CooldownType};
-use data::classes::CharacterClass;
-use data::Stat;
+use std::collections::{BTreeMap, BTreeSet};
+
+use data::{
+    classes::CharacterClass,
+    crafting::Recipe,
+    items::{get_item_by_type, ItemType},
+};
+use futures::TryStreamExt;
+use market::{Listing, ListingFilter, ListingKind, ListingStatus};
 use mongodb::{
-    bson::{doc, oid::ObjectId},
+    bson::{doc, oid::ObjectId, ser, to_document, Document},
     error::Error,
+    options::FindOptions,
     results::UpdateResult,
     Client, Collection, Database as MongoDatabase,
 };
+use trade::TradeOffer;
 use user_model::UserData;
 
 #[derive(Debug, Clone)]
@@ -35,11 +49,19 @@ impl Database {
     }
 
     pub fn user_collection(&self) -> Collection<UserData> {
-        self.db().collection_with_type::<UserData>("user")
+        self.db().collection::<UserData>("user")
     }
 
     pub fn cooldown_collection(&self) -> Collection<CooldownData> {
-        self.db().collection_with_type::<CooldownData>("cooldown")
+        self.db().collection::<CooldownData>("cooldown")
+    }
+
+    pub fn battle_log_collection(&self) -> Collection<BattleLog> {
+        self.db().collection::<BattleLog>("battle")
+    }
+
+    pub fn listing_collection(&self) -> Collection<Listing> {
+        self.db().collection::<Listing>("listing")
     }
 
     pub async fn create_user_cooldown(
@@ -129,12 +151,7 @@ impl Database {
     ) -> Result<(), Error> {
         let user_collection = self.user_collection();
 
-        let mut user = UserData::new(user_id.into(), class.class_type);
-        user.health = Stat::new(class.health);
-        user.mana = Stat::new(class.mana);
-        user.strength = class.strength;
-        user.agility = class.agility;
-        user.intelligence = class.intelligence;
+        let user = UserData::new_with_class(user_id.into(), class);
 
         user_collection.insert_one(user, None).await?;
         Ok(())
@@ -161,8 +178,12 @@ impl Database {
         data.is_some()
     }
 
+    // Health and mana regenerate over time, so they're brought up to date whenever the data is loaded
     pub async fn get_user_data(&self, user_id: &String) -> Result<Option<UserData>, Error> {
-        let data = self.get_raw_user_data(user_id).await?;
+        let mut data = self.get_raw_user_data(user_id).await?;
+        if let Some(data) = &mut data {
+            data.regenerate(Utc::now().timestamp_millis());
+        }
 
         Ok(data)
     }
@@ -174,4 +195,961 @@ impl Database {
             .replace_one(doc! { "userId": data.user_id.clone() }, data, None)
             .await?)
     }
+
+    // Only writes what changed since `original` was loaded. Gold and items are incremented instead
+    // of replaced, so trades, listings and crafts finished in the meantime are kept. Nothing is
+    // changed if the user no longer has the gold or items being spent.
+    pub async fn save_user_data_changes(
+        &self,
+        original: &UserData,
+        data: UserData,
+    ) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let (filter, update) = changes_update(original, &data)?;
+
+        let items: BTreeSet<ItemType> = original
+            .inventory
+            .keys()
+            .chain(data.inventory.keys())
+            .copied()
+            .collect();
+
+        let result = user_collection.update_one(filter, update, None).await?;
+
+        if result.matched_count == 0 {
+            return Ok(false);
+        }
+
+        self.remove_empty_items(&data.user_id, items.into_iter())
+            .await?;
+
+        Ok(true)
+    }
+
+    // Inputs are only consumed if the user still has all of them and room for the output,
+    // so two crafts running at the same time can't spend the same materials
+    pub async fn craft_item(
+        &self,
+        user_id: &String,
+        recipe: &Recipe,
+        times: i32,
+    ) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let (filter, update) = craft_update(user_id, recipe, times);
+
+        let result = user_collection.update_one(filter, update, None).await?;
+
+        if result.modified_count == 0 {
+            return Ok(false);
+        }
+
+        self.remove_empty_items(user_id, recipe.ingredients.iter().map(|i| i.item))
+            .await?;
+
+        Ok(true)
+    }
+
+    // Both sides are applied in a single transaction, if any of the players no longer has
+    // what they offered or has no room for what they receive, nothing is changed
+    pub async fn execute_trade(
+        &self,
+        first: (&String, &TradeOffer),
+        second: (&String, &TradeOffer),
+    ) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+
+        let mut session = self.client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        for (user_id, given, received) in
+            [(first.0, first.1, second.1), (second.0, second.1, first.1)]
+        {
+            let (filter, update) = trade_update(user_id, given, received);
+
+            let result = user_collection
+                .update_one_with_session(filter, update, None, &mut session)
+                .await?;
+
+            if result.matched_count == 0 {
+                session.abort_transaction().await?;
+                return Ok(false);
+            }
+        }
+
+        session.commit_transaction().await?;
+
+        for (user_id, offer) in [first, second] {
+            self.remove_empty_items(user_id, offer.items.keys().copied())
+                .await?;
+        }
+
+        Ok(true)
+    }
+
+    // Spent items are removed from the inventory instead of being kept with zero
+    async fn remove_empty_items(
+        &self,
+        user_id: &String,
+        items: impl Iterator<Item = ItemType>,
+    ) -> Result<(), Error> {
+        let user_collection = self.user_collection();
+
+        for item in items {
+            user_collection
+                .update_one(
+                    doc! {
+                        "userId": user_id,
+                        inventory_key(item): { "$lte": 0 }
+                    },
+                    doc! { "$unset": { inventory_key(item): "" } },
+                    None,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn save_battle_log(&self, log: BattleLog) -> Result<(), Error> {
+        let battle_log_collection = self.battle_log_collection();
+
+        battle_log_collection.insert_one(log, None).await?;
+        Ok(())
+    }
+
+    // Most recent battles first
+    pub async fn get_user_battle_logs(
+        &self,
+        user_id: &String,
+        limit: i64,
+    ) -> Result<Vec<BattleLog>, Error> {
+        let battle_log_collection = self.battle_log_collection();
+
+        let cursor = battle_log_collection
+            .find(
+                doc! {
+                    "participants.userId": user_id
+                },
+                FindOptions::builder()
+                    .sort(doc! { "createdAt": -1 })
+                    .limit(limit)
+                    .build(),
+            )
+            .await?;
+
+        cursor.try_collect().await
+    }
+
+    pub async fn count_user_listings(&self, user_id: &String) -> Result<u64, Error> {
+        let listing_collection = self.listing_collection();
+
+        listing_collection
+            .count_documents(
+                doc! {
+                    "sellerId": user_id,
+                    "status": ListingStatus::Active.to_string()
+                },
+                None,
+            )
+            .await
+    }
+
+    pub async fn get_listing(&self, listing_id: ObjectId) -> Result<Option<Listing>, Error> {
+        let listing_collection = self.listing_collection();
+
+        listing_collection
+            .find_one(doc! { "_id": listing_id }, None)
+            .await
+    }
+
+    // Most recent listings first, along with how many listings match the filter
+    pub async fn get_active_listings(
+        &self,
+        filter: &ListingFilter,
+        page: u64,
+        per_page: i64,
+    ) -> Result<(Vec<Listing>, u64), Error> {
+        let listing_collection = self.listing_collection();
+
+        let mut query = doc! {
+            "status": ListingStatus::Active.to_string(),
+            "expiresAt": { "$gt": Utc::now().timestamp_millis() }
+        };
+
+        if let Some(kind) = filter.kind {
+            query.insert("kind", kind.to_string());
+        }
+
+        if let Some(items) = &filter.items {
+            let items: Vec<String> = items.iter().map(|&i| item_key(i)).collect();
+            query.insert("item", doc! { "$in": items });
+        }
+
+        let total = listing_collection
+            .count_documents(query.clone(), None)
+            .await?;
+
+        let cursor = listing_collection
+            .find(
+                query,
+                FindOptions::builder()
+                    .sort(doc! { "createdAt": -1 })
+                    .skip(page * per_page as u64)
+                    .limit(per_page)
+                    .build(),
+            )
+            .await?;
+
+        Ok((cursor.try_collect().await?, total))
+    }
+
+    // The listed items leave the seller's inventory and are held by the listing until it's settled
+    pub async fn create_listing(&self, listing: Listing) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let listing_collection = self.listing_collection();
+
+        let mut session = self.client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        let result = user_collection
+            .update_one_with_session(
+                doc! {
+                    "userId": &listing.seller_id,
+                    inventory_key(listing.item): { "$gte": listing.amount }
+                },
+                doc! { "$inc": { inventory_key(listing.item): -listing.amount } },
+                None,
+                &mut session,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            session.abort_transaction().await?;
+            return Ok(false);
+        }
+
+        listing_collection
+            .insert_one_with_session(&listing, None, &mut session)
+            .await?;
+
+        session.commit_transaction().await?;
+
+        self.remove_empty_items(&listing.seller_id, [listing.item].into_iter())
+            .await?;
+
+        Ok(true)
+    }
+
+    pub async fn buy_listing(
+        &self,
+        listing_id: ObjectId,
+        buyer_id: &String,
+    ) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let listing_collection = self.listing_collection();
+
+        let mut session = self.client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        let (filter, update) = purchase_update(listing_id, buyer_id, Utc::now().timestamp_millis());
+        let listing = listing_collection
+            .find_one_and_update_with_session(filter, update, None, &mut session)
+            .await?;
+
+        let Some(listing) = listing else {
+            session.abort_transaction().await?;
+            return Ok(false);
+        };
+
+        let (filter, update) = payment_update(buyer_id, &listing);
+        let result = user_collection
+            .update_one_with_session(filter, update, None, &mut session)
+            .await?;
+
+        if result.matched_count == 0 {
+            session.abort_transaction().await?;
+            return Ok(false);
+        }
+
+        user_collection
+            .update_one_with_session(
+                doc! { "userId": &listing.seller_id },
+                doc! { "$inc": { "gold": listing.price } },
+                None,
+                &mut session,
+            )
+            .await?;
+
+        session.commit_transaction().await?;
+        Ok(true)
+    }
+
+    // The bid is taken from the bidder right away and the previous highest bidder is refunded
+    pub async fn place_bid(
+        &self,
+        listing_id: ObjectId,
+        bidder_id: &String,
+        amount: i32,
+    ) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let listing_collection = self.listing_collection();
+
+        let mut session = self.client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        // The listing is returned as it was before the bid
+        let (filter, update) =
+            bid_update(listing_id, bidder_id, amount, Utc::now().timestamp_millis());
+        let listing = listing_collection
+            .find_one_and_update_with_session(filter, update, None, &mut session)
+            .await?;
+
+        let Some(listing) = listing else {
+            session.abort_transaction().await?;
+            return Ok(false);
+        };
+
+        let result = user_collection
+            .update_one_with_session(
+                doc! { "userId": bidder_id, "gold": { "$gte": amount } },
+                doc! { "$inc": { "gold": -amount } },
+                None,
+                &mut session,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            session.abort_transaction().await?;
+            return Ok(false);
+        }
+
+        if let Some(previous) = listing.highest_bid {
+            user_collection
+                .update_one_with_session(
+                    doc! { "userId": previous.user_id },
+                    doc! { "$inc": { "gold": previous.amount } },
+                    None,
+                    &mut session,
+                )
+                .await?;
+        }
+
+        session.commit_transaction().await?;
+        Ok(true)
+    }
+
+    // Only listings without bids can be cancelled, the items go back to the seller
+    pub async fn cancel_listing(
+        &self,
+        listing_id: ObjectId,
+        seller_id: &String,
+    ) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let listing_collection = self.listing_collection();
+
+        let mut session = self.client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        let listing = listing_collection
+            .find_one_and_update_with_session(
+                doc! {
+                    "_id": listing_id,
+                    "sellerId": seller_id,
+                    "status": ListingStatus::Active.to_string(),
+                    "highestBid": null
+                },
+                doc! { "$set": { "status": ListingStatus::Cancelled.to_string() } },
+                None,
+                &mut session,
+            )
+            .await?;
+
+        let Some(listing) = listing else {
+            session.abort_transaction().await?;
+            return Ok(false);
+        };
+
+        user_collection
+            .update_one_with_session(
+                doc! { "userId": seller_id },
+                doc! { "$inc": { inventory_key(listing.item): listing.amount } },
+                None,
+                &mut session,
+            )
+            .await?;
+
+        session.commit_transaction().await?;
+        Ok(true)
+    }
+
+    // Returns how many listings were settled
+    pub async fn settle_expired_listings(&self) -> Result<usize, Error> {
+        let listing_collection = self.listing_collection();
+
+        let cursor = listing_collection
+            .find(
+                doc! {
+                    "status": ListingStatus::Active.to_string(),
+                    "expiresAt": { "$lte": Utc::now().timestamp_millis() }
+                },
+                None,
+            )
+            .await?;
+
+        let listings: Vec<Listing> = cursor.try_collect().await?;
+
+        let mut settled = 0;
+        for listing in listings {
+            if self.settle_listing(&listing).await? {
+                settled += 1;
+            }
+        }
+
+        Ok(settled)
+    }
+
+    // The highest bidder gets the items and the seller the gold, without bids the items go back to
+    // the seller. Items are always delivered, even past the stack limit, so nothing is lost
+    async fn settle_listing(&self, listing: &Listing) -> Result<bool, Error> {
+        let user_collection = self.user_collection();
+        let listing_collection = self.listing_collection();
+
+        let mut session = self.client.start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        let (filter, update) = settlement_update(listing);
+        let result = listing_collection
+            .update_one_with_session(filter, update, None, &mut session)
+            .await?;
+
+        // Someone else settled it first
+        if result.matched_count == 0 {
+            session.abort_transaction().await?;
+            return Ok(false);
+        }
+
+        let receiver_id = match &listing.highest_bid {
+            Some(bid) => {
+                user_collection
+                    .update_one_with_session(
+                        doc! { "userId": &listing.seller_id },
+                        doc! { "$inc": { "gold": bid.amount } },
+                        None,
+                        &mut session,
+                    )
+                    .await?;
+
+                &bid.user_id
+            }
+            None => &listing.seller_id,
+        };
+
+        user_collection
+            .update_one_with_session(
+                doc! { "userId": receiver_id },
+                doc! { "$inc": { inventory_key(listing.item): listing.amount } },
+                None,
+                &mut session,
+            )
+            .await?;
+
+        session.commit_transaction().await?;
+        Ok(true)
+    }
+}
+
+// Item types are serialized by their variant name, which is also their Debug output
+fn item_key(item: ItemType) -> String {
+    format!("{:?}", item)
+}
+
+fn inventory_key(item: ItemType) -> String {
+    format!("inventory.{}", item_key(item))
+}
+
+// Builds the update of `save_user_data_changes`. Health, mana and the last regeneration are always
+// written because `get_user_data` already regenerated them in `original` too. The filter makes
+// sure the user still has the gold and items being spent.
+fn changes_update(
+    original: &UserData,
+    data: &UserData,
+) -> Result<(Document, Document), ser::Error> {
+    let original_document = to_document(original)?;
+
+    let mut changes = Document::new();
+    for (key, value) in to_document(data)? {
+        if ["_id", "userId", "gold", "inventory"].contains(&key.as_str()) {
+            continue;
+        }
+
+        if !["health", "mana", "lastUpdated"].contains(&key.as_str())
+            && original_document.get(&key) == Some(&value)
+        {
+            continue;
+        }
+
+        changes.insert(key, value);
+    }
+
+    let items: BTreeSet<ItemType> = original
+        .inventory
+        .keys()
+        .chain(data.inventory.keys())
+        .copied()
+        .collect();
+
+    let mut filter = doc! { "userId": &data.user_id };
+
+    let gold = data.gold - original.gold;
+    if gold < 0 {
+        filter.insert("gold", doc! { "$gte": -gold });
+    }
+
+    let mut increments = doc! { "gold": gold };
+    for item in items {
+        let amount = data.item_amount(item) - original.item_amount(item);
+        if amount < 0 {
+            filter.insert(inventory_key(item), doc! { "$gte": -amount });
+        }
+        if amount != 0 {
+            increments.insert(inventory_key(item), amount);
+        }
+    }
+
+    Ok((filter, doc! { "$inc": increments, "$set": changes }))
+}
+
+// Builds the update of one side of a trade, the filter makes sure the player still owns what
+// they're giving and has room for what they're receiving
+fn trade_update(
+    user_id: &String,
+    given: &TradeOffer,
+    received: &TradeOffer,
+) -> (Document, Document) {
+    let mut filter = doc! {
+        "userId": user_id,
+        "gold": { "$gte": given.gold }
+    };
+    let mut increments = doc! { "gold": received.gold - given.gold };
+
+    let items: BTreeSet<ItemType> = given
+        .items
+        .keys()
+        .chain(received.items.keys())
+        .copied()
+        .collect();
+
+    for item in items {
+        let given_amount = given.item_amount(item);
+        let difference = received.item_amount(item) - given_amount;
+        let max_stack = get_item_by_type(item).map_or(0, |i| i.max_stack);
+
+        let mut condition = Document::new();
+        if given_amount > 0 {
+            condition.insert("$gte", given_amount);
+        }
+        if difference > 0 {
+            condition.insert("$not", doc! { "$gt": max_stack - difference });
+        }
+
+        if !condition.is_empty() {
+            filter.insert(inventory_key(item), condition);
+        }
+        increments.insert(inventory_key(item), difference);
+    }
+
+    (filter, doc! { "$inc": increments })
+}
+
+// Builds the update of a craft, the filter makes sure the player still has every ingredient and
+// room for the output
+fn craft_update(user_id: &String, recipe: &Recipe, times: i32) -> (Document, Document) {
+    let output_amount = recipe.amount * times;
+
+    // An item may be used by more than one ingredient or also be the output, so the amounts
+    // are merged to end up with a single condition and increment per item
+    let mut needed: BTreeMap<ItemType, i32> = BTreeMap::new();
+    for ingredient in recipe.ingredients {
+        *needed.entry(ingredient.item).or_default() += ingredient.amount * times;
+    }
+
+    let mut changes: BTreeMap<ItemType, i32> = needed
+        .iter()
+        .map(|(&item, &amount)| (item, -amount))
+        .collect();
+    *changes.entry(recipe.output).or_default() += output_amount;
+
+    let mut filter = doc! { "userId": user_id };
+    for (&item, &amount) in needed.iter() {
+        filter.insert(inventory_key(item), doc! { "$gte": amount });
+    }
+
+    let max_amount = recipe.item().max_stack - changes[&recipe.output];
+    match filter.get_document_mut(inventory_key(recipe.output)) {
+        Ok(condition) => {
+            condition.insert("$lte", max_amount);
+        }
+        Err(_) => {
+            filter.insert(
+                inventory_key(recipe.output),
+                doc! { "$not": { "$gt": max_amount } },
+            );
+        }
+    }
+
+    let mut increments = Document::new();
+    for (&item, &amount) in changes.iter() {
+        increments.insert(inventory_key(item), amount);
+    }
+
+    (filter, doc! { "$inc": increments })
+}
+
+// Marks a fixed price listing as sold, as long as it's still for sale and not the buyer's own
+fn purchase_update(listing_id: ObjectId, buyer_id: &String, now: i64) -> (Document, Document) {
+    (
+        doc! {
+            "_id": listing_id,
+            "kind": ListingKind::FixedPrice.to_string(),
+            "status": ListingStatus::Active.to_string(),
+            "sellerId": { "$ne": buyer_id },
+            "expiresAt": { "$gt": now }
+        },
+        doc! {
+            "$set": {
+                "status": ListingStatus::Sold.to_string(),
+                "buyerId": buyer_id
+            }
+        },
+    )
+}
+
+// Takes the price from the buyer, the filter makes sure they can pay it and have room for the items
+fn payment_update(buyer_id: &String, listing: &Listing) -> (Document, Document) {
+    let max_stack = get_item_by_type(listing.item).map_or(0, |i| i.max_stack);
+
+    (
+        doc! {
+            "userId": buyer_id,
+            "gold": { "$gte": listing.price },
+            inventory_key(listing.item): { "$not": { "$gt": max_stack - listing.amount } }
+        },
+        doc! {
+            "$inc": {
+                "gold": -listing.price,
+                inventory_key(listing.item): listing.amount
+            }
+        },
+    )
+}
+
+// Replaces the highest bid of an auction, as long as the new one is at least the minimum bid
+fn bid_update(
+    listing_id: ObjectId,
+    bidder_id: &String,
+    amount: i32,
+    now: i64,
+) -> (Document, Document) {
+    // Same rule as `Listing::minimum_bid`, checked in the filter so it also holds for
+    // concurrent bids
+    let minimum_bid = doc! {
+        "$add": [
+            "$highestBid.amount",
+            { "$max": [{ "$floor": { "$divide": ["$highestBid.amount", 10] } }, 1] }
+        ]
+    };
+
+    (
+        doc! {
+            "_id": listing_id,
+            "kind": ListingKind::Auction.to_string(),
+            "status": ListingStatus::Active.to_string(),
+            "sellerId": { "$ne": bidder_id },
+            "expiresAt": { "$gt": now },
+            "$or": [
+                { "highestBid": null, "price": { "$lte": amount } },
+                {
+                    "highestBid": { "$ne": null },
+                    "$expr": { "$lte": [minimum_bid, amount] }
+                }
+            ]
+        },
+        doc! {
+            "$set": {
+                "highestBid": { "userId": bidder_id, "amount": amount }
+            }
+        },
+    )
+}
+
+// Closes a listing that is still active, as sold to the highest bidder or as expired
+fn settlement_update(listing: &Listing) -> (Document, Document) {
+    let update = match &listing.highest_bid {
+        Some(bid) => doc! {
+            "$set": {
+                "status": ListingStatus::Sold.to_string(),
+                "buyerId": &bid.user_id
+            }
+        },
+        None => doc! { "$set": { "status": ListingStatus::Expired.to_string() } },
+    };
+
+    (
+        doc! {
+            "_id": listing.id,
+            "status": ListingStatus::Active.to_string()
+        },
+        update,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use data::crafting::Ingredient;
+    use market::Bid;
+
+    use super::*;
+
+    fn max_stack(item: ItemType) -> i32 {
+        get_item_by_type(item).unwrap().max_stack
+    }
+
+    #[test]
+    fn craft_update_consumes_ingredients_and_checks_room_for_the_output() {
+        const INGREDIENTS: &[Ingredient] = &[
+            Ingredient::new(ItemType::Slime, 3),
+            Ingredient::new(ItemType::Wood, 1),
+        ];
+        let recipe = Recipe {
+            output: ItemType::HealthPotion,
+            amount: 2,
+            ingredients: INGREDIENTS,
+        };
+
+        let (filter, update) = craft_update(&"1".to_string(), &recipe, 2);
+
+        assert_eq!(
+            filter,
+            doc! {
+                "userId": "1",
+                "inventory.Slime": { "$gte": 6 },
+                "inventory.Wood": { "$gte": 2 },
+                "inventory.HealthPotion": {
+                    "$not": { "$gt": max_stack(ItemType::HealthPotion) - 4 }
+                }
+            }
+        );
+        assert_eq!(
+            update,
+            doc! {
+                "$inc": {
+                    "inventory.HealthPotion": 4,
+                    "inventory.Slime": -6,
+                    "inventory.Wood": -2
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn craft_update_merges_repeated_items() {
+        const INGREDIENTS: &[Ingredient] = &[
+            Ingredient::new(ItemType::HealthPotion, 2),
+            Ingredient::new(ItemType::Slime, 1),
+            Ingredient::new(ItemType::Slime, 2),
+        ];
+        let recipe = Recipe {
+            output: ItemType::HealthPotion,
+            amount: 1,
+            ingredients: INGREDIENTS,
+        };
+
+        let (filter, update) = craft_update(&"1".to_string(), &recipe, 1);
+
+        assert_eq!(
+            filter,
+            doc! {
+                "userId": "1",
+                "inventory.HealthPotion": {
+                    "$gte": 2,
+                    "$lte": max_stack(ItemType::HealthPotion) + 1
+                },
+                "inventory.Slime": { "$gte": 3 }
+            }
+        );
+        assert_eq!(
+            update,
+            doc! {
+                "$inc": {
+                    "inventory.HealthPotion": -1,
+                    "inventory.Slime": -3
+                }
+            }
+        );
+    }
+
+    fn listing(kind: ListingKind) -> Listing {
+        Listing::new(
+            "2".to_string(),
+            ItemType::Wood,
+            3,
+            kind,
+            50,
+            Duration::hours(1),
+        )
+    }
+
+    #[test]
+    fn purchase_update_only_matches_fixed_price_listings_of_others() {
+        let id = ObjectId::new();
+
+        let (filter, update) = purchase_update(id, &"1".to_string(), 1000);
+
+        assert_eq!(
+            filter,
+            doc! {
+                "_id": id,
+                "kind": "FixedPrice",
+                "status": "Active",
+                "sellerId": { "$ne": "1" },
+                "expiresAt": { "$gt": 1000_i64 }
+            }
+        );
+        assert_eq!(
+            update,
+            doc! { "$set": { "status": "Sold", "buyerId": "1" } }
+        );
+    }
+
+    #[test]
+    fn payment_update_checks_gold_and_room_for_the_items() {
+        let listing = listing(ListingKind::FixedPrice);
+
+        let (filter, update) = payment_update(&"1".to_string(), &listing);
+
+        assert_eq!(
+            filter,
+            doc! {
+                "userId": "1",
+                "gold": { "$gte": 50 },
+                "inventory.Wood": { "$not": { "$gt": max_stack(ItemType::Wood) - 3 } }
+            }
+        );
+        assert_eq!(
+            update,
+            doc! { "$inc": { "gold": -50, "inventory.Wood": 3 } }
+        );
+    }
+
+    #[test]
+    fn bid_update_requires_the_price_or_the_minimum_bid() {
+        let id = ObjectId::new();
+
+        let (filter, update) = bid_update(id, &"1".to_string(), 60, 1000);
+
+        assert_eq!(filter.get_str("kind").unwrap(), "Auction");
+        assert_eq!(filter.get_str("status").unwrap(), "Active");
+        assert_eq!(
+            filter.get_document("expiresAt").unwrap(),
+            &doc! { "$gt": 1000_i64 }
+        );
+        assert_eq!(
+            filter.get_array("$or").unwrap()[0],
+            doc! { "highestBid": null, "price": { "$lte": 60 } }.into()
+        );
+        assert_eq!(
+            filter.get_array("$or").unwrap()[1],
+            doc! {
+                "highestBid": { "$ne": null },
+                "$expr": {
+                    "$lte": [
+                        {
+                            "$add": [
+                                "$highestBid.amount",
+                                { "$max": [{ "$floor": { "$divide": ["$highestBid.amount", 10] } }, 1] }
+                            ]
+                        },
+                        60
+                    ]
+                }
+            }
+            .into()
+        );
+        assert_eq!(
+            update,
+            doc! { "$set": { "highestBid": { "userId": "1", "amount": 60 } } }
+        );
+    }
+
+    #[test]
+    fn settlement_update_sells_to_the_highest_bidder_or_expires() {
+        let mut listing = listing(ListingKind::Auction);
+
+        let (filter, update) = settlement_update(&listing);
+        assert_eq!(filter, doc! { "_id": listing.id, "status": "Active" });
+        assert_eq!(update, doc! { "$set": { "status": "Expired" } });
+
+        listing.highest_bid = Some(Bid {
+            user_id: "1".to_string(),
+            amount: 60,
+        });
+
+        let (_, update) = settlement_update(&listing);
+        assert_eq!(
+            update,
+            doc! { "$set": { "status": "Sold", "buyerId": "1" } }
+        );
+    }
+
+    #[test]
+    fn changes_update_increments_gold_and_items_and_sets_regeneration() {
+        let original = UserData::default();
+        let mut data = original.clone();
+        data.add_gold(10);
+        data.add_item(ItemType::Wood, 2);
+
+        let (filter, update) = changes_update(&original, &data).unwrap();
+
+        // Nothing is spent, so only the user is matched
+        assert_eq!(filter, doc! { "userId": &original.user_id });
+        assert_eq!(
+            update.get_document("$inc").unwrap(),
+            &doc! { "gold": 10, "inventory.Wood": 2 }
+        );
+
+        // Regeneration is always written, even when nothing else changed
+        let set = update.get_document("$set").unwrap();
+        let mut keys = set.keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, ["health", "lastUpdated", "mana"]);
+        assert_eq!(set.get_i64("lastUpdated").unwrap(), original.last_updated);
+    }
+
+    #[test]
+    fn changes_update_checks_the_gold_and_items_being_spent() {
+        let mut original = UserData::default();
+        original.add_gold(100);
+        original.add_item(ItemType::Wood, 5);
+
+        let mut data = original.clone();
+        data.remove_gold(30);
+        data.remove_item(ItemType::Wood, 2);
+        data.add_item(ItemType::IronOre, 1);
+
+        let (filter, update) = changes_update(&original, &data).unwrap();
+
+        assert_eq!(
+            filter,
+            doc! {
+                "userId": &original.user_id,
+                "gold": { "$gte": 30 },
+                "inventory.Wood": { "$gte": 2 }
+            }
+        );
+        assert_eq!(
+            update.get_document("$inc").unwrap(),
+            &doc! { "gold": -30, "inventory.Wood": -2, "inventory.IronOre": 1 }
+        );
+    }
 }
@@ -0,0 +1,116 @@
+use std::fmt::Display;
+
+use chrono::{Duration, Utc};
+use data::items::ItemType;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListingKind {
+    FixedPrice,
+    Auction,
+}
+
+impl ListingKind {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ListingKind::FixedPrice => "Preço fixo",
+            ListingKind::Auction => "Leilão",
+        }
+    }
+}
+
+impl Display for ListingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListingStatus {
+    Active,
+    Sold,
+    Expired,
+    Cancelled,
+}
+
+impl Display for ListingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bid {
+    pub user_id: String,
+    pub amount: i32,
+}
+
+// The listed items are held by the listing, and so is the gold of the highest bid, until it's settled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub seller_id: String,
+    pub item: ItemType,
+    pub amount: i32,
+    pub kind: ListingKind,
+    /// The price of a fixed price listing, or the minimum first bid of an auction
+    pub price: i32,
+    pub highest_bid: Option<Bid>,
+    pub buyer_id: Option<String>,
+    pub status: ListingStatus,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+impl Listing {
+    pub fn new(
+        seller_id: String,
+        item: ItemType,
+        amount: i32,
+        kind: ListingKind,
+        price: i32,
+        duration: Duration,
+    ) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: ObjectId::new(),
+            seller_id,
+            item,
+            amount,
+            kind,
+            price,
+            highest_bid: None,
+            buyer_id: None,
+            status: ListingStatus::Active,
+            created_at: now.timestamp_millis(),
+            expires_at: (now + duration).timestamp_millis(),
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        self.expires_at <= Utc::now().timestamp_millis()
+    }
+
+    // Each new bid must raise the current one by at least 10%
+    pub fn minimum_bid(&self) -> i32 {
+        match &self.highest_bid {
+            Some(bid) => bid.amount + (bid.amount / 10).max(1),
+            None => self.price,
+        }
+    }
+
+    pub fn current_price(&self) -> i32 {
+        self.highest_bid.as_ref().map_or(self.price, |b| b.amount)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListingFilter {
+    pub kind: Option<ListingKind>,
+    pub items: Option<Vec<ItemType>>,
+}
@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+use data::items::ItemType;
+
+// Goods one of the players puts on the table, they're only moved when the trade is settled
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TradeOffer {
+    pub gold: i32,
+    pub items: BTreeMap<ItemType, i32>,
+}
+
+impl TradeOffer {
+    pub fn is_empty(&self) -> bool {
+        self.gold <= 0 && self.items.values().all(|&amount| amount <= 0)
+    }
+
+    pub fn item_amount(&self, item: ItemType) -> i32 {
+        self.items.get(&item).copied().unwrap_or(0)
+    }
+
+    pub fn add_item(&mut self, item: ItemType, amount: i32) {
+        *self.items.entry(item).or_insert(0) += amount;
+    }
+}
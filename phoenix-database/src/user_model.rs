@@ -1,6 +1,9 @@
-use data::{classes::ClassType, regions::{RegionType, REGIONS}, anomalies::AnomalyType};
+use std::collections::BTreeMap;
+
+use data::{classes::{Attribute, CharacterClass, ClassType, ALL_ATTRIBUTES}, regions::{RegionType, REGIONS}, anomalies::{AnomalyType, AnomalyVariant}, crafting::Recipe, items::{get_item_by_type, EquipmentSlot, EquipmentStats, ItemType}, passives::PassiveEffect, skill_trees::{get_skill_node_by_type, get_subclass_by_type, skill_points_for_level, SkillNode, SkillNodeEffect, SkillNodeType, Subclass, SubclassType, SUBCLASS_LEVEL}, skills::SkillType};
+use chrono::Utc;
 use mongodb::bson::oid::ObjectId;
-use rand::{seq::{SliceRandom, IteratorRandom}, Rng, thread_rng};
+use rand::{seq::IteratorRandom, Rng, thread_rng};
 use serde::{Deserialize, Serialize};
 
 use data::Stat;
@@ -27,6 +30,12 @@ const fn default_level() -> i32 {
     1
 }
 
+const REGENERATION_TICK_MILLIS: i64 = 60 * 1000;
+/// Fraction of the max health regenerated every minute
+pub const HEALTH_REGENERATION_PER_MINUTE: f32 = 0.01;
+/// Fraction of the max mana regenerated every minute
+pub const MANA_REGENERATION_PER_MINUTE: f32 = 0.02;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Region {
@@ -37,7 +46,7 @@ pub struct Region {
 
 impl Default for Region {
     fn default() -> Self {
-        Self::new_with_random_name(0.0, RegionType::default())
+        Self::new_with_random_name(&mut thread_rng(), 0.0, RegionType::default())
     }
 }
 
@@ -50,23 +59,23 @@ impl Region {
         }
     }
 
-    pub fn new_with_random_name(distance: f32, region_type: RegionType) -> Self {
-        let name = region_type.generate_specific_name();
+    pub fn new_with_random_name(rng: &mut impl Rng, distance: f32, region_type: RegionType) -> Self {
+        let name = region_type.generate_specific_name(rng);
         Self::new(distance, name, region_type)
     }
 
-    pub fn generate_random_from_journey(journey: Journey) -> Self {
+    pub fn generate_random_from_journey(rng: &mut impl Rng, journey: Journey) -> Self {
         let last_region = journey.region_history.last();
         if !last_region.map_or(false, |r| r.region_type != RegionType::City) {
-            return Self::new_with_random_name(journey.total_traveled, RegionType::City);
+            return Self::new_with_random_name(rng, journey.total_traveled, RegionType::City);
         }
 
         let region_type = REGIONS.iter().copied()
             .filter(|r| *r != journey.current_region.region_type)
-            .choose(&mut thread_rng())
+            .choose(rng)
             .unwrap_or_default();
 
-        Self::new_with_random_name(journey.total_traveled, region_type)
+        Self::new_with_random_name(rng, journey.total_traveled, region_type)
     }
 
     pub const fn emoji(&self) -> &'static str { self.region_type.emoji() }
@@ -77,24 +86,81 @@ impl Region {
 pub struct Journey {
     pub total_traveled: f32,
     pub current_region: Region,
-    pub region_history: Vec<Region>
+    pub region_history: Vec<Region>,
+    // Set when the end of the current region is reached, the region's boss must be defeated to leave it
+    #[serde(default)]
+    pub boss_pending: bool
 }
 
 impl Journey {
     pub fn new() -> Self {
         Self {
             total_traveled: 0.0,
-            current_region: Region::new_with_random_name(0.0, RegionType::default()),
-            region_history: Vec::new()
+            current_region: Region::new_with_random_name(&mut thread_rng(), 0.0, RegionType::default()),
+            region_history: Vec::new(),
+            boss_pending: false
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterResult {
+    Won,
+    Lost,
+    Fled,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BestiaryEntry {
     pub anomaly: AnomalyType,
+    #[serde(default)]
+    pub variant: Option<AnomalyVariant>,
     pub wins: i32,
     pub loses: i32,
+    #[serde(default)]
+    pub flees: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BossKill {
+    pub anomaly: AnomalyType,
+    pub region: String,
+    pub killed_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Equipment {
+    pub weapon: Option<ItemType>,
+    pub armor: Option<ItemType>,
+    pub accessory: Option<ItemType>,
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipmentSlot) -> Option<ItemType> {
+        match slot {
+            EquipmentSlot::Weapon => self.weapon,
+            EquipmentSlot::Armor => self.armor,
+            EquipmentSlot::Accessory => self.accessory,
+        }
+    }
+
+    fn get_mut(&mut self, slot: EquipmentSlot) -> &mut Option<ItemType> {
+        match slot {
+            EquipmentSlot::Weapon => &mut self.weapon,
+            EquipmentSlot::Armor => &mut self.armor,
+            EquipmentSlot::Accessory => &mut self.accessory,
+        }
+    }
+
+    pub fn stats(&self) -> EquipmentStats {
+        [self.weapon, self.armor, self.accessory]
+            .iter()
+            .flatten()
+            .filter_map(|&item| get_item_by_type(item)?.equipment())
+            .fold(EquipmentStats::NONE, |total, (_, stats)| total + stats)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,6 +182,12 @@ pub struct UserData {
     pub journey: Journey,
     #[serde(default)]
     pub bestiary: Vec<BestiaryEntry>,
+    #[serde(default)]
+    pub boss_kills: Vec<BossKill>,
+    #[serde(default)]
+    pub inventory: BTreeMap<ItemType, i32>,
+    #[serde(default)]
+    pub equipment: Equipment,
     #[serde(default = "default_strength")]
     pub strength: i32,
     #[serde(default = "default_agi_intel")]
@@ -126,6 +198,17 @@ pub struct UserData {
     pub xp: i32,
     #[serde(default = "default_level")]
     pub level: i32,
+    // Earned when leveling up, spent by the player with /atributos
+    #[serde(default)]
+    pub attribute_points: i32,
+    #[serde(default)]
+    pub subclass: Option<SubclassType>,
+    // Skill tree nodes unlocked with /talentos
+    #[serde(default)]
+    pub skill_nodes: Vec<SkillNodeType>,
+    // Last time health and mana were regenerated, in milliseconds
+    #[serde(default)]
+    pub last_updated: i64,
 }
 
 impl UserData {
@@ -139,18 +222,27 @@ impl UserData {
         }
     }
 
+    pub fn new_with_class(user_id: String, class: CharacterClass) -> Self {
+        Self {
+            health: Stat::new(class.health),
+            mana: Stat::new(class.mana),
+            strength: class.strength,
+            agility: class.agility,
+            intelligence: class.intelligence,
+            ..Self::new(user_id, class.class_type)
+        }
+    }
+
     pub fn add_xp(&mut self, amount: i32) {
         self.xp += amount
     }
 
     // Returns the new User's level or None if not leveled up
-    pub fn level_up(&mut self) -> Option<i32> {
+    pub fn level_up(&mut self, rng: &mut impl Rng) -> Option<i32> {
         if self.xp < util::math::calculate_xp_required_for_level_up(self.level) {
             return None;
         }
 
-        let rng = &mut rand::thread_rng();
-
         let mut attributes_points = 5;
 
         let (lower_range, upper_range) = ((self.level / 3).max(1), (self.level / 2).max(2));
@@ -161,29 +253,7 @@ impl UserData {
             attributes_points += rng.gen_range(lower_range..=upper_range);
         }
 
-        while attributes_points > 0 {
-            let common_upgrades: Vec<Box<dyn Fn(&mut UserData, i32) -> ()>> = vec![
-                Box::new(UserData::add_max_health),
-                Box::new(UserData::add_max_mana),
-            ];
-            let rare_upgrades: Vec<Box<dyn Fn(&mut UserData, i32) -> ()>> = vec![
-                Box::new(UserData::add_strength),
-                Box::new(UserData::add_intelligence),
-                Box::new(UserData::add_agility),
-            ];
-
-            let upgrades: Vec<Box<dyn Fn(&mut UserData, i32) -> ()>> = match rng.gen_bool(0.7) {
-                true => common_upgrades,
-                _ => rare_upgrades
-            };
-
-            let Some(upgrade) = upgrades.choose(rng) else {
-                break;
-            };
-
-            upgrade(self, 1);
-            attributes_points -= 1;
-        }
+        self.attribute_points += attributes_points;
 
         Some(self.level)
     }
@@ -195,25 +265,178 @@ impl UserData {
     pub fn travel_to_region(&mut self, region: Region)  {
         self.journey.region_history.push(self.journey.current_region.clone());
         self.journey.current_region = region;
+        self.journey.boss_pending = false;
     }
 
-    pub fn try_add_to_bestiary(&mut self, anomaly: AnomalyType, won: bool) {
-        let entry = self.bestiary.iter_mut().find(|e| e.anomaly == anomaly);
+    pub fn add_boss_kill(&mut self, anomaly: AnomalyType, region: String) {
+        self.boss_kills.push(BossKill {
+            anomaly,
+            region,
+            killed_at: Utc::now().timestamp_millis(),
+        });
+    }
 
-        if let Some(entry) = entry {
-            if won {
-                entry.wins += 1;
-            } else {
-                entry.loses += 1;
+    pub fn try_add_to_bestiary(
+        &mut self,
+        anomaly: AnomalyType,
+        variant: Option<AnomalyVariant>,
+        result: EncounterResult,
+    ) {
+        let entry = match self
+            .bestiary
+            .iter_mut()
+            .position(|e| e.anomaly == anomaly && e.variant == variant)
+        {
+            Some(index) => &mut self.bestiary[index],
+            None => {
+                self.bestiary.push(BestiaryEntry {
+                    anomaly,
+                    variant,
+                    wins: 0,
+                    loses: 0,
+                    flees: 0,
+                });
+                self.bestiary.last_mut().unwrap()
             }
+        };
+
+        match result {
+            EncounterResult::Won => entry.wins += 1,
+            EncounterResult::Lost => entry.loses += 1,
+            EncounterResult::Fled => entry.flees += 1,
+        }
+    }
+
+    pub fn item_amount(&self, item: ItemType) -> i32 {
+        self.inventory.get(&item).copied().unwrap_or(0)
+    }
+
+    pub fn has_item(&self, item: ItemType, amount: i32) -> bool {
+        self.item_amount(item) >= amount
+    }
+
+    /// Adds as many items as the stack limit allows, returning how many were actually added
+    pub fn add_item(&mut self, item: ItemType, amount: i32) -> i32 {
+        let max_stack = get_item_by_type(item).map_or(0, |i| i.max_stack);
+        let current = self.item_amount(item);
+        let added = amount.clamp(0, (max_stack - current).max(0));
+
+        if added > 0 {
+            self.inventory.insert(item, current + added);
+        }
+
+        added
+    }
+
+    pub fn remove_item(&mut self, item: ItemType, amount: i32) -> bool {
+        let current = self.item_amount(item);
+        if amount <= 0 || current < amount {
+            return false;
+        }
+
+        if current == amount {
+            self.inventory.remove(&item);
+        } else {
+            self.inventory.insert(item, current - amount);
+        }
+
+        true
+    }
+
+    pub fn can_craft(&self, recipe: &Recipe, times: i32) -> bool {
+        let space = recipe.item().max_stack - self.item_amount(recipe.output);
+
+        // Checking the times first keeps the amounts below from overflowing
+        times <= space
+            && space >= recipe.amount * times
+            && recipe
+                .ingredients
+                .iter()
+                .all(|i| self.has_item(i.item, i.amount * times))
+    }
+
+    /// Moves the item from the inventory to its slot, sending the previous one back to the inventory
+    pub fn equip(&mut self, item: ItemType) -> bool {
+        let Some((slot, _)) = get_item_by_type(item).and_then(|i| i.equipment()) else {
+            return false;
+        };
+
+        if !self.has_item(item, 1) {
+            return false;
+        }
+
+        self.remove_item(item, 1);
+        if let Some(previous) = self.equipment.get(slot) {
+            if self.add_item(previous, 1) == 0 {
+                self.add_item(item, 1);
+                return false;
+            }
+        }
+
+        *self.equipment.get_mut(slot) = Some(item);
+        true
+    }
+
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> bool {
+        let Some(item) = self.equipment.get(slot) else {
+            return false;
+        };
+
+        if self.add_item(item, 1) == 0 {
+            return false;
+        }
+
+        *self.equipment.get_mut(slot) = None;
+        true
+    }
+
+    // Health and mana given by equipment and the skill tree are the first to be lost in battle
+    pub fn set_health_after_battle(&mut self, health: i32) {
+        self.set_health(health - self.bonus_stats().health);
+    }
+
+    pub fn set_mana_after_battle(&mut self, mana: i32) {
+        self.set_mana(mana - self.bonus_stats().mana);
+    }
+
+    /// Regenerates the health and mana recovered since the last update, one minute at a time,
+    /// so loading the data often doesn't lose the time left for the next minute
+    pub fn regenerate(&mut self, now: i64) {
+        let is_full = self.health.value >= self.health.max && self.mana.value >= self.mana.max;
+        if self.last_updated <= 0 || is_full {
+            self.last_updated = now;
             return;
         }
 
-        self.bestiary.push(BestiaryEntry { 
-            anomaly: anomaly, 
-            wins: if won { 1 } else { 0 }, 
-            loses: if won { 0 } else { 1 }
-        });
+        let ticks = ((now - self.last_updated) / REGENERATION_TICK_MILLIS).max(0);
+        if ticks == 0 {
+            return;
+        }
+
+        let amount = |max: i32, rate: f32| {
+            (ticks * regeneration_per_tick(max, rate) as i64).min(max as i64) as i32
+        };
+        self.add_health(amount(self.health.max, HEALTH_REGENERATION_PER_MINUTE));
+        self.add_mana(amount(self.mana.max, MANA_REGENERATION_PER_MINUTE));
+        self.last_updated += ticks * REGENERATION_TICK_MILLIS;
+    }
+
+    /// Timestamp in milliseconds of when health and mana will be full again, or None if they already are
+    pub fn full_regeneration_at(&self) -> Option<i64> {
+        let ticks_for = |stat: &Stat, rate: f32| {
+            let missing = (stat.max - stat.value).max(0) as i64;
+            let per_tick = regeneration_per_tick(stat.max, rate) as i64;
+            (missing + per_tick - 1) / per_tick
+        };
+
+        let ticks = ticks_for(&self.health, HEALTH_REGENERATION_PER_MINUTE)
+            .max(ticks_for(&self.mana, MANA_REGENERATION_PER_MINUTE));
+
+        if ticks == 0 {
+            return None;
+        }
+
+        Some(self.last_updated + ticks * REGENERATION_TICK_MILLIS)
     }
 
     pub fn add_gold(&mut self, amount: i32) {
@@ -275,6 +498,147 @@ impl UserData {
     pub fn add_agility(&mut self, amount: i32) {
         self.agility += amount;
     }
+
+    pub fn attribute(&self, attribute: Attribute) -> i32 {
+        match attribute {
+            Attribute::Health => self.health.max,
+            Attribute::Mana => self.mana.max,
+            Attribute::Strength => self.strength,
+            Attribute::Agility => self.agility,
+            Attribute::Intelligence => self.intelligence,
+        }
+    }
+
+    pub fn spend_attribute_points(&mut self, attribute: Attribute, amount: i32) -> bool {
+        if amount <= 0 || self.attribute_points < amount {
+            return false;
+        }
+
+        match attribute {
+            Attribute::Health => self.add_max_health(amount),
+            Attribute::Mana => self.add_max_mana(amount),
+            Attribute::Strength => self.add_strength(amount),
+            Attribute::Agility => self.add_agility(amount),
+            Attribute::Intelligence => self.add_intelligence(amount),
+        }
+
+        self.attribute_points -= amount;
+        true
+    }
+
+    /// Sets every attribute back to the class base and refunds the points spent on them,
+    /// returning how many points were refunded
+    pub fn reset_attributes(&mut self, class: &CharacterClass) -> i32 {
+        let refunded: i32 = ALL_ATTRIBUTES
+            .iter()
+            .map(|&a| (self.attribute(a) - class.base_attribute(a)).max(0))
+            .sum();
+
+        self.health.max = class.health;
+        self.health.set_value(self.health.value);
+        self.mana.max = class.mana;
+        self.mana.set_value(self.mana.value);
+        self.strength = class.strength;
+        self.agility = class.agility;
+        self.intelligence = class.intelligence;
+
+        self.attribute_points += refunded;
+        refunded
+    }
+
+    pub fn get_subclass(&self) -> Option<Subclass> {
+        self.subclass.and_then(get_subclass_by_type)
+    }
+
+    pub fn can_choose_subclass(&self, subclass: &Subclass) -> bool {
+        self.subclass.is_none() && self.level >= SUBCLASS_LEVEL && subclass.class == self.class
+    }
+
+    pub fn choose_subclass(&mut self, subclass: &Subclass) -> bool {
+        if !self.can_choose_subclass(subclass) {
+            return false;
+        }
+
+        self.subclass = Some(subclass.subclass_type);
+        true
+    }
+
+    pub fn unlocked_skill_nodes(&self) -> Vec<SkillNode> {
+        self.skill_nodes
+            .iter()
+            .copied()
+            .filter_map(get_skill_node_by_type)
+            .collect()
+    }
+
+    pub fn has_skill_node(&self, node: SkillNodeType) -> bool {
+        self.skill_nodes.contains(&node)
+    }
+
+    pub fn skill_points(&self) -> i32 {
+        let spent: i32 = self.unlocked_skill_nodes().iter().map(|n| n.cost).sum();
+        skill_points_for_level(self.level) - spent
+    }
+
+    pub fn can_unlock_skill_node(&self, node: &SkillNode) -> bool {
+        node.class == self.class
+            && (node.subclass.is_none() || node.subclass == self.subclass)
+            && !self.has_skill_node(node.node_type)
+            && node.requires.is_none_or(|n| self.has_skill_node(n))
+            && self.level >= node.required_level
+            && self.skill_points() >= node.cost
+    }
+
+    pub fn unlock_skill_node(&mut self, node: &SkillNode) -> bool {
+        if !self.can_unlock_skill_node(node) {
+            return false;
+        }
+
+        self.skill_nodes.push(node.node_type);
+        true
+    }
+
+    fn skill_node_effects(&self) -> Vec<SkillNodeEffect> {
+        self.unlocked_skill_nodes()
+            .iter()
+            .flat_map(|n| n.effects.iter().copied())
+            .collect()
+    }
+
+    // Stats that are not part of the user's attributes, given by equipment and the skill tree
+    pub fn bonus_stats(&self) -> EquipmentStats {
+        self.skill_node_effects()
+            .iter()
+            .fold(self.equipment.stats(), |total, effect| match effect {
+                SkillNodeEffect::Stats(stats) => total + *stats,
+                _ => total,
+            })
+    }
+
+    // Skills learned from the subclass and the skill tree, on top of the class ones
+    pub fn learned_skills(&self) -> Vec<SkillType> {
+        let nodes = self.skill_node_effects();
+        let node_skills = nodes.iter().filter_map(|effect| match effect {
+            SkillNodeEffect::Skill(skill) => Some(*skill),
+            _ => None,
+        });
+
+        self.get_subclass().map(|s| s.skill).into_iter().chain(node_skills).collect()
+    }
+
+    pub fn passives(&self) -> Vec<PassiveEffect> {
+        self.skill_node_effects()
+            .iter()
+            .filter_map(|effect| match effect {
+                SkillNodeEffect::Passive(passive) => Some(*passive),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn regeneration_per_tick(max: i32, rate: f32) -> i32 {
+    ((max as f32 * rate).ceil() as i32).max(1)
 }
 
 impl Default for UserData {
@@ -288,11 +652,68 @@ impl Default for UserData {
             mana: Stat::new(20),
             journey: default_journey(),
             bestiary: Vec::new(),
+            boss_kills: Vec::new(),
+            inventory: BTreeMap::new(),
+            equipment: Equipment::default(),
             strength: default_strength(),
             agility: default_agi_intel(),
             intelligence: default_agi_intel(),
             xp: default_xp(),
             level: default_level(),
+            attribute_points: 0,
+            subclass: None,
+            skill_nodes: Vec::new(),
+            last_updated: Utc::now().timestamp_millis(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wounded() -> UserData {
+        let mut data = UserData::default();
+        data.health.value = 50;
+        data.mana.value = 0;
+        data.last_updated = 1000;
+        data
+    }
+
+    #[test]
+    fn regenerate_restores_a_share_of_the_max_every_minute() {
+        let mut data = wounded();
+
+        data.regenerate(1000 + 10 * REGENERATION_TICK_MILLIS + 30 * 1000);
+
+        assert_eq!(data.health.value, 60);
+        assert_eq!(data.mana.value, 10);
+        // The unfinished minute is kept for the next regeneration
+        assert_eq!(data.last_updated, 1000 + 10 * REGENERATION_TICK_MILLIS);
+    }
+
+    #[test]
+    fn regenerate_only_moves_the_clock_when_full() {
+        let mut data = UserData {
+            last_updated: 1000,
+            ..UserData::default()
+        };
+
+        data.regenerate(1000 + 10 * REGENERATION_TICK_MILLIS);
+
+        assert_eq!(data.health.value, data.health.max);
+        assert_eq!(data.last_updated, 1000 + 10 * REGENERATION_TICK_MILLIS);
+    }
+
+    #[test]
+    fn full_regeneration_waits_for_the_slowest_stat() {
+        let data = wounded();
+
+        // Health takes 50 minutes and mana 20
+        assert_eq!(
+            data.full_regeneration_at(),
+            Some(1000 + 50 * REGENERATION_TICK_MILLIS)
+        );
+        assert_eq!(UserData::default().full_regeneration_at(), None);
+    }
+}
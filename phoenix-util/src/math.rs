@@ -15,3 +15,20 @@ pub fn calculate_critical_chance(intelligence_a: i32, intelligence_b: i32) -> Pr
 pub fn calculate_xp_required_for_level_up(level: i32) -> i32 {
     (100f32 * (level as f32 * 1.5)) as i32
 }
+
+pub fn calculate_flee_chance(agility_a: i32, agility_b: i32) -> Probability {
+    let ratio = (agility_a + 1) as f32 / (agility_b + 1) as f32;
+
+    Probability::new((ratio * 40.0).min(90.0) as u8)
+}
+
+// Only fighters at least twice as fast as their slowest opponent can act twice in a turn cycle
+pub fn calculate_extra_action_chance(agility_a: i32, agility_b: i32) -> Probability {
+    let ratio = (agility_a + 1) as f32 / (agility_b + 1) as f32;
+
+    if ratio < 2.0 {
+        return Probability::new(0);
+    }
+
+    Probability::new(((ratio - 1.0) * 15.0).min(50.0) as u8)
+}
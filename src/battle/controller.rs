@@ -1,47 +1,297 @@
 use std::time::Duration;
 
 use async_recursion::async_recursion;
+use format as f;
+use tokio_stream::StreamExt;
 use twilight_model::{
     application::interaction::{Interaction, InteractionData},
     channel::message::{
-        component::{ActionRow, Button, ButtonStyle},
+        component::ButtonStyle,
         Component, ReactionType,
     },
+    id::Id,
 };
 
 use crate::{
     commands::prelude::{CommandContext, DynamicError, Response},
     discord::{
-        component::{ActionRowBuilder, ButtonBuilder},
+        component::{ActionRowBuilder, ButtonBuilder, SelectMenuBuilder},
         embed::{EmbedAuthor, EmbedBuilder, EmbedField},
-        extensions::{StandbyExtension, UserExtension},
+        extensions::StandbyExtension,
         pagination::EmbedPagination,
     },
 };
+use data::{
+    anomalies::{get_anomaly_from_type, AnomalyDrops},
+    effects::StatusEffectType,
+    elements::{Effectiveness, Element},
+    items::get_item_by_type,
+    skills::{get_skill_by_type, Skill},
+    Emoji,
+};
+use database::battle_log::{
+    BattleLog, BattleLogAction, BattleLogAnomaly, BattleLogFighter, BattleLogParticipant,
+    BattleLogRound,
+};
 use util::Color;
 
-use super::{ActionType, Battle, BattleResult, ALL_ACTION_TYPES};
+use super::{
+    ActionType, Battle, BattleEvent, BattleResult, Fighter, Round, ALL_ACTION_TYPES, PLAYER_TEAM,
+};
+
+const TARGET_SELECT_ID: &str = "target";
+const USE_ITEM_BUTTON_ID: &str = "use_item";
+const ITEM_SELECT_ID: &str = "item";
+
+fn team_emoji(fighter: &Fighter) -> &'static str {
+    if fighter.team == PLAYER_TEAM {
+        "🔵"
+    } else {
+        "🔴"
+    }
+}
+
+fn render_event(battle: &Battle, event: &BattleEvent) -> String {
+    let name = |index: usize| &battle.fighters()[index].name;
+    let critical_message = |critical: bool| {
+        if critical {
+            "\n(**ACERTO CRÍTICO!** 💥)"
+        } else {
+            ""
+        }
+    };
+
+    match *event {
+        BattleEvent::Attack {
+            attacker,
+            target,
+            damage,
+            critical,
+        } => f!(
+            "**{}** atacou **{}** com um golpe simples, que causou **{}** de dano.{}",
+            name(attacker),
+            name(target),
+            damage,
+            critical_message(critical)
+        ),
+        BattleEvent::SkillUsed { fighter, skill } => {
+            let skill = get_skill_by_type(skill).expect("Invalid skill type");
+            f!(
+                "**{}** usou {} **{}**! (-{} {})",
+                name(fighter),
+                skill.emoji,
+                skill.name,
+                skill.mana_cost,
+                Emoji::Mana
+            )
+        }
+        BattleEvent::NotEnoughMana { fighter, skill } => f!(
+            "**{}** tentou usar **{}**, mas não tinha mana suficiente!",
+            name(fighter),
+            ActionType::Skill(skill).name()
+        ),
+        BattleEvent::Dodge { fighter } => f!("🪶 **{}** esquivou!", name(fighter)),
+        BattleEvent::Damage {
+            fighter,
+            amount,
+            critical,
+        } => f!(
+            "**{}** recebeu **{}** de dano.{}",
+            name(fighter),
+            amount,
+            critical_message(critical)
+        ),
+        BattleEvent::Heal { fighter, amount } => {
+            f!("**{}** recuperou **{}** de vida.", name(fighter), amount)
+        }
+        BattleEvent::ManaDrain { fighter, amount } => {
+            f!("**{}** perdeu **{}** de mana.", name(fighter), amount)
+        }
+        BattleEvent::ManaRestored { fighter, amount } => {
+            f!("**{}** recuperou **{}** de mana.", name(fighter), amount)
+        }
+        BattleEvent::ItemUsed { fighter, item } => {
+            let item = get_item_by_type(item).expect("Invalid item type");
+            f!(
+                "**{}** usou {} **{}**!",
+                name(fighter),
+                item.emoji,
+                item.name
+            )
+        }
+        BattleEvent::StatusInflicted { fighter, effect } => f!(
+            "{} **{}** recebeu **{}** por {} rodadas! ({})",
+            effect.effect_type.emoji(),
+            name(fighter),
+            effect.effect_type.name(),
+            effect.turns,
+            effect.effect_type.description()
+        ),
+        BattleEvent::StatusTick {
+            fighter,
+            effect_type,
+            amount,
+        } => match effect_type {
+            StatusEffectType::Poison | StatusEffectType::Burn => f!(
+                "{} **{}** sofreu **{}** de dano de {}.",
+                effect_type.emoji(),
+                name(fighter),
+                amount,
+                effect_type.name()
+            ),
+            StatusEffectType::Regeneration => f!(
+                "{} **{}** regenerou **{}** de vida.",
+                effect_type.emoji(),
+                name(fighter),
+                amount
+            ),
+            StatusEffectType::Stun => f!(
+                "{} **{}** está atordoado e perdeu a vez!",
+                effect_type.emoji(),
+                name(fighter)
+            ),
+        },
+        BattleEvent::StatusExpired {
+            fighter,
+            effect_type,
+        } => f!(
+            "**{}** não está mais sob efeito de {} **{}**.",
+            name(fighter),
+            effect_type.emoji(),
+            effect_type.name()
+        ),
+        BattleEvent::Defend { fighter } => f!(
+            "🛡️ **{}** se defendeu e receberá menos dano até sua próxima rodada.",
+            name(fighter)
+        ),
+        BattleEvent::Fled { fighter } => f!("🏃 **{}** fugiu da batalha!", name(fighter)),
+        BattleEvent::FleeFailed { fighter, chance } => f!(
+            "🏃 **{}** tentou fugir, mas não conseguiu! (`{}` de chance)",
+            name(fighter),
+            chance
+        ),
+        BattleEvent::BossPhase { fighter, phase } => {
+            let message = battle.fighters()[fighter]
+                .boss()
+                .and_then(|b| b.phases.get(phase).map(|p| p.message))
+                .unwrap_or("mudou de forma!");
+
+            f!("👑 **{}** {}", name(fighter), message)
+        }
+        BattleEvent::Effectiveness {
+            fighter,
+            effectiveness,
+        } => match effectiveness {
+            Effectiveness::Strong => f!(
+                "{} É super efetivo contra **{}**!",
+                effectiveness.emoji(),
+                name(fighter)
+            ),
+            _ => f!(
+                "{} Não é muito efetivo contra **{}**...",
+                effectiveness.emoji(),
+                name(fighter)
+            ),
+        },
+    }
+}
+
+pub fn render_round_messages(battle: &Battle, round: &Round) -> Vec<String> {
+    round
+        .events
+        .iter()
+        .map(|e| render_event(battle, e))
+        .collect()
+}
+
+pub fn render_round_title(battle: &Battle, round: &Round) -> String {
+    let fighter = &battle.fighters()[round.fighter];
+
+    match round.action {
+        Some(action) => f!("{} usou {}", fighter.name, action.name()),
+        None => f!("{} perdeu a vez", fighter.name),
+    }
+}
+
+fn get_round_embed(battle: &Battle, round: &Round) -> EmbedBuilder {
+    let fighter = &battle.fighters()[round.fighter];
+
+    EmbedBuilder::new()
+        .set_author(EmbedAuthor {
+            name: render_round_title(battle, round),
+            icon_url: Some(fighter.image()),
+        })
+        .set_thumbnail(fighter.image())
+        .set_color(Color::LIGHT_ORANGE)
+        .set_description(render_round_messages(battle, round).join("\n"))
+        .set_current_timestamp()
+}
+
+// Tells players which element their target has, the skill buttons show how effective each one is
+fn element_hint(battle: &Battle) -> String {
+    let target = battle.target_fighter();
+    if battle.current_fighter().user_id.is_none() || target.element == Element::Neutral {
+        return String::new();
+    }
+
+    f!(
+        "\n{} **{}** é do elemento **{}**: {} super efetivo, {} pouco efetivo.",
+        target.element.emoji(),
+        target.name,
+        target.element.name(),
+        Effectiveness::Strong.emoji(),
+        Effectiveness::Weak.emoji()
+    )
+}
 
 fn get_battle_embed(battle: &Battle) -> EmbedBuilder {
     let current_fighter = battle.current_fighter();
 
+    let turn_order = battle
+        .turn_order()
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            if i == 0 {
+                f!("**{}**", f.name)
+            } else {
+                f.name.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ➜ ");
+
     EmbedBuilder::new()
         .set_color(Color::BLURPLE)
         .set_author(EmbedAuthor {
             name: format!("Rodada de {}", current_fighter.name),
-            icon_url: current_fighter.user.as_ref().map(|u| u.avatar_url()),
+            icon_url: Some(current_fighter.image()),
         })
+        .set_description(f!(
+            "⏱️ Ordem de ação: {}{}",
+            turn_order,
+            element_hint(battle)
+        ))
         .add_fields(
             &mut battle
-                .fighters
+                .fighters()
                 .iter()
                 .cloned()
                 .map(|f| {
-                    let target = battle.fighters.get(f.target_index.unwrap()).unwrap();
+                    let value = match f.target_index.and_then(|i| battle.fighters().get(i)) {
+                        Some(target) if f.health.value > 0 => {
+                            f!(
+                                "{}\n🎯 Alvo: **{}**",
+                                f.display_full_stats_with_target(target),
+                                target.name
+                            )
+                        }
+                        _ => f.display_full_stats(),
+                    };
 
                     EmbedField {
-                        name: f.name.to_string(),
-                        value: f.display_full_stats_with_target(target),
+                        name: f!("{} {}", team_emoji(&f), f.name),
+                        value,
                         inline: true,
                     }
                 })
@@ -50,67 +300,210 @@ fn get_battle_embed(battle: &Battle) -> EmbedBuilder {
         .set_current_timestamp()
 }
 
-fn get_battle_action_components(_battle: &Battle) -> Component {
-    ActionRowBuilder::new()
-        .add_buttons(
-            ALL_ACTION_TYPES
-                .iter()
-                .copied()
-                .map(|a| {
-                    ButtonBuilder::new()
-                        .set_custom_id(a.name())
-                        .set_emoji(ReactionType::Unicode {
-                            name: a.emoji().into(),
-                        })
-                        .set_label(a.name())
+// Marks the skills that are effective or not against the target
+fn skill_hint(skill: &Skill, target: &Fighter) -> String {
+    if !skill.deals_damage() {
+        return String::new();
+    }
+
+    match target.effectiveness_of(skill.element) {
+        Effectiveness::Neutral => String::new(),
+        effectiveness => f!(" {}", effectiveness.emoji()),
+    }
+}
+
+fn get_battle_action_components(battle: &Battle, show_items: bool) -> Vec<Component> {
+    let fighter = battle.current_fighter();
+    let items = fighter.usable_items();
+
+    let mut buttons: Vec<ButtonBuilder> = ALL_ACTION_TYPES
+        .iter()
+        .copied()
+        .map(|a| {
+            ButtonBuilder::new()
+                .set_custom_id(a.name())
+                .set_emoji(ReactionType::Unicode {
+                    name: a.emoji().into(),
                 })
-                .collect(),
-        )
-        .build()
+                .set_label(a.name())
+        })
+        .collect();
+
+    buttons.push(
+        ButtonBuilder::new()
+            .set_custom_id(USE_ITEM_BUTTON_ID)
+            .set_emoji(ReactionType::Unicode {
+                name: "🎒".into()
+            })
+            .set_label("Usar item")
+            .set_disabled(items.is_empty()),
+    );
+
+    let mut components = vec![ActionRowBuilder::new().add_buttons(buttons).build()];
+
+    let opponents = battle.opponents_of(battle.current_fighter_index());
+    if opponents.len() > 1 {
+        let mut select_menu =
+            SelectMenuBuilder::new(TARGET_SELECT_ID).set_placeholder("Escolha um alvo");
+
+        for index in opponents {
+            let opponent = &battle.fighters()[index];
+            select_menu = select_menu.add_option(
+                &opponent.name,
+                index,
+                Some(f!(
+                    "{} {}/{}",
+                    Emoji::Health,
+                    opponent.health.value,
+                    opponent.health.max
+                )),
+                None,
+                fighter.target_index == Some(index),
+            );
+        }
+
+        components.push(ActionRowBuilder::new().add_select_menu(select_menu).build());
+    }
+
+    let skills = fighter.available_skills();
+    let target = battle.target_fighter();
+    if !skills.is_empty() {
+        components.push(
+            ActionRowBuilder::new()
+                .add_buttons(
+                    skills
+                        .iter()
+                        .map(|s| {
+                            ButtonBuilder::new()
+                                .set_custom_id(s.name)
+                                .set_emoji(ReactionType::Unicode {
+                                    name: s.emoji.into(),
+                                })
+                                .set_label(f!(
+                                    "{} ({} {}){}",
+                                    s.name,
+                                    s.mana_cost,
+                                    Emoji::Mana,
+                                    skill_hint(s, target)
+                                ))
+                                .set_style(ButtonStyle::Primary)
+                                .set_disabled(!fighter.can_afford(s))
+                        })
+                        .collect(),
+                )
+                .build(),
+        );
+    }
+
+    if show_items && !items.is_empty() {
+        let mut select_menu =
+            SelectMenuBuilder::new(ITEM_SELECT_ID).set_placeholder("Escolha um item para usar");
+
+        for (i, (item, amount)) in items.iter().enumerate() {
+            select_menu = select_menu.add_option(
+                f!("{} ({}x)", item.name, amount),
+                i,
+                Some(item.display_effects().replace("**", "")),
+                Some(ReactionType::Unicode {
+                    name: item.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        components.push(ActionRowBuilder::new().add_select_menu(select_menu).build());
+    }
+
+    components
+}
+
+fn get_battle_response(battle: &Battle, show_items: bool) -> Response {
+    Response {
+        embeds: Some(vec![get_battle_embed(battle)]),
+        components: Some(get_battle_action_components(battle, show_items)),
+        ..Default::default()
+    }
 }
 
 async fn wait_for_battle_action(
     ctx: &CommandContext,
-    battle: Battle,
+    battle: &mut Battle,
 ) -> Result<Option<ActionType>, DynamicError> {
-    let author_id = ctx.author_id()?;
-
-    if let Some(anomaly) = battle.current_fighter().anomaly {
-        return Ok(Some(battle.current_fighter().choose_action_type(&battle)));
-    }
+    let Some(user_id) = battle.current_fighter().user_id else {
+        return Ok(Some(battle.choose_ai_action()));
+    };
 
     let message = ctx
-        .send_in_channel(Response {
-            embeds: Some(vec![get_battle_embed(&battle)]),
-            components: Some(vec![get_battle_action_components(&battle)]),
-            ..Default::default()
-        })
+        .send_in_channel(get_battle_response(battle, false))
         .await?;
 
-    let user = battle.current_fighter().user.clone().unwrap();
-
     let standby = ctx.standby.clone();
-    let Ok(Some(component)) = standby.wait_for_component_with_duration(message.id, Duration::from_secs(500), move |event: &Interaction| {
-        event.author_id() == Some(user.id)
-    }).await else {
-        return Ok(None);
-    };
+    let mut stream = standby.create_component_stream(
+        message.id,
+        Duration::from_secs(500),
+        move |event: &Interaction| event.author_id() == Some(Id::new(user_id)),
+    );
+
+    while let Some(Ok(component)) = stream.next().await {
+        let Some(InteractionData::MessageComponent(data)) = &component.data else {
+            return Ok(None);
+        };
+
+        let ctx = CommandContext::from_with_interaction(ctx, Box::new(component.clone()));
+
+        // Picking a target keeps the turn open until an action is chosen
+        if data.custom_id == TARGET_SELECT_ID {
+            let target = data
+                .values
+                .first()
+                .and_then(|v| v.parse::<usize>().ok())
+                .ok_or("Invalid target")?;
+
+            battle.set_target(target)?;
+            ctx.update_interaction(get_battle_response(battle, false))
+                .await
+                .ok();
+            continue;
+        }
 
-    let Some(InteractionData::MessageComponent(data)) = &component.data else {
-        return Ok(None);
-    };
+        if data.custom_id == USE_ITEM_BUTTON_ID {
+            ctx.update_interaction(get_battle_response(battle, true))
+                .await
+                .ok();
+            continue;
+        }
 
-    let action = ActionType::from_name(&data.custom_id).ok_or("Invalid action")?;
+        let action = if data.custom_id == ITEM_SELECT_ID {
+            let items = battle.current_fighter().usable_items();
+            let (item, _) = data
+                .values
+                .first()
+                .and_then(|v| v.parse::<usize>().ok())
+                .and_then(|i| items.get(i))
+                .ok_or("Invalid item")?;
+
+            ActionType::UseItem(item.item_type)
+        } else {
+            ActionType::from_name(&data.custom_id).ok_or("Invalid action")?
+        };
+
+        // Only actions the fighter can actually take end the turn
+        if !battle.can_take_action(action) {
+            ctx.update_interaction(get_battle_response(battle, false))
+                .await
+                .ok();
+            continue;
+        }
 
-    let interaction = Box::new(component);
-    let ctx = CommandContext::from_with_interaction(ctx, interaction.clone());
+        ctx.update_interaction(Response::from_embeds(vec![get_battle_embed(battle)]))
+            .await
+            .ok();
+        ctx.delete_message(message).await.ok();
 
-    ctx.update_interaction(Response::from_embeds(vec![get_battle_embed(&battle)]))
-        .await
-        .ok();
-    ctx.delete_message(message).await.ok();
+        return Ok(Some(action));
+    }
 
-    Ok(Some(action))
+    Ok(None)
 }
 
 #[async_recursion]
@@ -118,14 +511,18 @@ pub async fn handle_battle(
     ctx: &CommandContext,
     battle: &mut Battle,
 ) -> Result<BattleResult, DynamicError> {
-    let action = wait_for_battle_action(ctx, battle.clone())
-        .await?
-        .ok_or("Invalid action")?;
-
-    let round = battle.run_action(action);
+    let round = if battle.current_fighter().is_stunned() {
+        battle.skip_turn()
+    } else {
+        let action = wait_for_battle_action(ctx, battle)
+            .await?
+            .ok_or("Invalid action")?;
+
+        battle.run_action(action)?
+    };
 
     let message = ctx
-        .send_in_channel(Response::from_embeds(vec![round.into()]))
+        .send_in_channel(Response::from_embeds(vec![get_round_embed(battle, &round)]))
         .await
         .ok();
 
@@ -145,60 +542,314 @@ async fn check_or_handle_battle(
     ctx: &CommandContext,
     battle: &mut Battle,
 ) -> Result<BattleResult, DynamicError> {
-    let Some(winner) = &battle.winner else {
+    if !battle.finished() {
         return handle_battle(ctx, battle).await;
+    }
+
+    let last_round = battle.rounds().last().ok_or("Last round not found")?;
+    let last_action = render_round_messages(battle, last_round)
+        .last()
+        .cloned()
+        .unwrap_or("?".to_string());
+
+    let winners = battle.winners();
+    let (mut embed, highlighted_fighter) = match (winners.first(), battle.fled_fighter()) {
+        (Some(winner), _) => {
+            let names = winners
+                .iter()
+                .map(|w| w.name.clone())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            let mut survivors = winners
+                .iter()
+                .filter(|w| w.health.value > 0)
+                .map(|w| f!("**{}**: {} vida restando", w.name, w.health))
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            // Everyone may fall in the same turn, like to thorns
+            if survivors.is_empty() {
+                survivors = "Ninguém ficou de pé, mas o último golpe foi deles.".to_string();
+            }
+
+            (
+                EmbedBuilder::new()
+                    .set_color(Color::GREEN)
+                    .set_author(EmbedAuthor {
+                        name: if winners.len() > 1 {
+                            format!("{} venceram!", names)
+                        } else {
+                            format!("{} venceu!", names)
+                        },
+                        icon_url: Some(winner.image()),
+                    })
+                    .set_thumbnail(winner.image())
+                    .set_description(survivors),
+                winner.clone(),
+            )
+        }
+        (None, Some(fled)) => (
+            EmbedBuilder::new()
+                .set_color(Color::YELLOW)
+                .set_author(EmbedAuthor {
+                    name: format!("{} fugiu!", fled.name),
+                    icon_url: Some(fled.image()),
+                })
+                .set_thumbnail(fled.image())
+                .set_description(format!(
+                    "{} fugiu com {} vida restando. A batalha terminou sem vencedores.",
+                    fled.name, fled.health
+                )),
+            fled.clone(),
+        ),
+        _ => Err("Battle finished without a winner or a fled fighter")?,
     };
 
-    let embed = EmbedBuilder::new()
-        .set_color(Color::GREEN)
-        .set_author(EmbedAuthor {
-            name: format!("{} venceu!", winner.name),
-            icon_url: winner.user.clone().map(|u| u.avatar_url()),
-        })
-        .set_thumbnail(winner.image())
-        .set_description(format!(
-            "{} venceu com {} vida restando!",
-            winner.name, winner.health
-        ))
+    let embed = embed
         .add_field(EmbedField {
             name: "📜 Última ação:".into(),
-            value: battle
-                .rounds
-                .last()
-                .ok_or("Last round not found")?
-                .messages
-                .last()
-                .unwrap_or(&"?".to_string())
-                .to_string(),
+            value: last_action,
             inline: true,
         })
+        .add_footer_text(f!("Semente: {}", battle.seed()))
         .set_current_timestamp();
 
+    tracing::info!(
+        "Battle finished with seed {} after {} rounds",
+        battle.seed(),
+        battle.rounds().len()
+    );
+
     let mut embeds: Vec<EmbedBuilder> = vec![embed];
-    let rounds = battle.rounds.clone();
+    embeds.append(&mut create_history_embeds(
+        render_rounds(battle),
+        highlighted_fighter.image(),
+    ));
+
+    if let Err(err) = ctx.db().save_battle_log(create_battle_log(battle)).await {
+        tracing::error!("Failed to save battle log: {}", err);
+    }
+
+    let ctx_clone = ctx.clone();
+    tokio::spawn(async move {
+        EmbedPagination::new(ctx_clone, embeds).send().await.ok();
+    });
+
+    //ctx.send_in_channel(Response::from_embeds(vec![embed]))
+    //    .await?;
+
+    Ok(battle.result())
+}
+
+fn log_action(action: ActionType) -> BattleLogAction {
+    match action {
+        ActionType::Attack => BattleLogAction::Attack,
+        ActionType::Defend => BattleLogAction::Defend,
+        ActionType::Flee => BattleLogAction::Flee,
+        ActionType::Skill(skill) => BattleLogAction::Skill(skill),
+        ActionType::UseItem(item) => BattleLogAction::UseItem(item),
+    }
+}
+
+fn action_from_log(action: BattleLogAction) -> ActionType {
+    match action {
+        BattleLogAction::Attack => ActionType::Attack,
+        BattleLogAction::Defend => ActionType::Defend,
+        BattleLogAction::Flee => ActionType::Flee,
+        BattleLogAction::Skill(skill) => ActionType::Skill(skill),
+        BattleLogAction::UseItem(item) => ActionType::UseItem(item),
+    }
+}
+
+fn log_fighter(fighter: &Fighter) -> BattleLogFighter {
+    BattleLogFighter {
+        name: fighter.name.clone(),
+        user_id: fighter.user_id.map(|id| id.to_string()),
+        image: fighter.image.clone(),
+        anomaly: fighter.anomaly.map(|a| BattleLogAnomaly {
+            anomaly_type: a.anomaly_type,
+            variant: a.variant,
+            level: a.level,
+            xp: a.rewards.xp,
+            gold: a.rewards.gold,
+            at_home: a.at_home,
+        }),
+        team: fighter.team as i32,
+        health: fighter.health,
+        mana: fighter.mana,
+        strength: fighter.strength,
+        agility: fighter.agility,
+        intelligence: fighter.intelligence,
+        element: fighter.element,
+        skills: fighter.skills.clone(),
+        passives: fighter.passives.clone(),
+        items: fighter.items.clone(),
+    }
+}
+
+fn fighter_from_log(fighter: &BattleLogFighter) -> Result<Fighter, DynamicError> {
+    let anomaly = match &fighter.anomaly {
+        Some(logged) => {
+            let mut anomaly =
+                get_anomaly_from_type(logged.anomaly_type).ok_or("Invalid anomaly")?;
+            anomaly.variant = logged.variant;
+            anomaly.health = fighter.health;
+            anomaly.mana = fighter.mana;
+            anomaly.strength = fighter.strength;
+            anomaly.agility = fighter.agility;
+            anomaly.intelligence = fighter.intelligence;
+            anomaly.level = logged.level;
+            anomaly.rewards = AnomalyDrops {
+                xp: logged.xp,
+                gold: logged.gold,
+            };
+            anomaly.at_home = logged.at_home;
+            Some(anomaly)
+        }
+        None => None,
+    };
+
+    Ok(Fighter {
+        name: fighter.name.clone(),
+        user_id: fighter
+            .user_id
+            .as_ref()
+            .map(|id| id.parse::<u64>())
+            .transpose()?,
+        image: fighter.image.clone(),
+        anomaly,
+        boss_phase: 0,
+        team: usize::try_from(fighter.team)?,
+        target_index: None,
+        health: fighter.health,
+        mana: fighter.mana,
+        strength: fighter.strength,
+        agility: fighter.agility,
+        intelligence: fighter.intelligence,
+        element: fighter.element,
+        skills: fighter.skills.clone(),
+        passives: fighter.passives.clone(),
+        has_struck: false,
+        defending: false,
+        effects: Vec::new(),
+        items: fighter.items.clone(),
+        used_items: Vec::new(),
+    })
+}
+
+fn create_battle_log(battle: &Battle) -> BattleLog {
+    let participants = battle
+        .fighters()
+        .iter()
+        .map(|f| BattleLogParticipant {
+            user_id: f.user_id.map(|id| id.to_string()),
+            name: f.name.clone(),
+            team: f.team as i32,
+            image: f.image(),
+        })
+        .collect();
+
+    let fighters = battle.initial_fighters().iter().map(log_fighter).collect();
+
+    let rounds = battle
+        .rounds()
+        .iter()
+        .map(|r| BattleLogRound {
+            fighter: r.fighter as i32,
+            target: r.target.map(|t| t as i32),
+            action: r.action.map(log_action),
+        })
+        .collect();
+
+    BattleLog::new(
+        participants,
+        fighters,
+        battle.seed(),
+        rounds,
+        battle.winning_team().map(|t| t as i32),
+        battle.fled_fighter().map(|f| f.name.clone()),
+    )
+}
+
+// Rebuilds the battle from its fighters, seed and actions
+fn replay_battle_log(log: &BattleLog) -> Result<Battle, DynamicError> {
+    let fighters = log
+        .fighters
+        .iter()
+        .map(fighter_from_log)
+        .collect::<Result<Vec<Fighter>, DynamicError>>()?;
+
+    let rounds = log
+        .rounds
+        .iter()
+        .map(|r| -> Result<Round, DynamicError> {
+            Ok(Round {
+                fighter: usize::try_from(r.fighter)?,
+                target: r.target.map(usize::try_from).transpose()?,
+                action: r.action.map(action_from_log),
+                events: Vec::new(),
+            })
+        })
+        .collect::<Result<Vec<Round>, DynamicError>>()?;
+
+    let seed = log.seed().ok_or("Invalid battle seed")?;
+    let battle = Battle::replay(fighters, seed, &rounds)?;
+
+    if battle.rounds().len() != rounds.len() {
+        tracing::warn!(
+            "Replay of battle {} produced {} rounds instead of {}",
+            log.id,
+            battle.rounds().len(),
+            rounds.len()
+        );
+    }
+
+    Ok(battle)
+}
+
+fn render_rounds(battle: &Battle) -> Vec<(String, Vec<String>)> {
+    battle
+        .rounds()
+        .iter()
+        .map(|r| {
+            (
+                render_round_title(battle, r),
+                render_round_messages(battle, r),
+            )
+        })
+        .collect()
+}
+
+/// Title and messages of each round of a saved battle
+pub fn render_battle_log(log: &BattleLog) -> Result<Vec<(String, Vec<String>)>, DynamicError> {
+    Ok(render_rounds(&replay_battle_log(log)?))
+}
+
+pub fn create_history_embeds(
+    rounds: Vec<(String, Vec<String>)>,
+    image: String,
+) -> Vec<EmbedBuilder> {
+    let mut embeds = Vec::new();
+
     for i in (0..rounds.len()).step_by(3) {
         let mut embed = EmbedBuilder::new()
             .set_author(EmbedAuthor {
                 name: "Histórico da batalha".to_string(),
-                icon_url: Some(winner.image()),
+                icon_url: Some(image.clone()),
             })
-            .set_thumbnail(winner.image())
+            .set_thumbnail(image.clone())
             .set_color(Color::LIGHT_ORANGE)
             .set_current_timestamp();
 
         for j in 0..3 {
-            let Some(round) = rounds.get(i + j) else {
+            let Some((title, messages)) = rounds.get(i + j) else {
                 break;
             };
 
             embed.add_field(EmbedField {
-                name: format!(
-                    "- **`#{}`**: {} usou {}",
-                    (i + j),
-                    round.fighter.name,
-                    round.action.action_type.name()
-                ),
-                value: round.messages.join("\n") + "\n",
+                name: format!("- **`#{}`**: {}", (i + j), title),
+                value: messages.join("\n") + "\n",
                 inline: false,
             });
         }
@@ -206,23 +857,5 @@ async fn check_or_handle_battle(
         embeds.push(embed);
     }
 
-    let ctx_clone = ctx.clone();
-    tokio::spawn(async move {
-        EmbedPagination::new(ctx_clone, embeds).send().await.ok();
-    });
-
-    //ctx.send_in_channel(Response::from_embeds(vec![embed]))
-    //    .await?;
-
-    Ok(BattleResult {
-        battle: battle.to_owned(),
-        all_fighters: battle.fighters.clone(),
-        defeated_fighters: battle
-            .fighters
-            .iter()
-            .cloned()
-            .filter(|f| f.health.value == 0)
-            .collect(),
-        winner: winner.to_owned(),
-    })
+    embeds
 }
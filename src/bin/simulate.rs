@@ -0,0 +1,319 @@
+// Runs headless battles between every class and anomaly to help balancing.
+// Usage: cargo run --bin simulate -- [--battles 1000] [--levels 1,5,10,20] [--seed 42] [--format csv|json]
+
+use battle::{ActionType, Battle, DynamicError, Fighter, PlayerStats};
+use data::{
+    anomalies::{self, AnomalyDefinition, ANOMALIES},
+    classes::{Attribute, CharacterClass, ALL_CLASSES},
+};
+use database::user_model::UserData;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use util::math;
+
+// Rough time a player takes to pick an action and how long the bot waits between rounds
+const PLAYER_ROUND_SECONDS: f32 = 5.0;
+const ANOMALY_ROUND_SECONDS: f32 = 1.0;
+// Time spent on the command itself, the encounter embed and the lobby
+const ENCOUNTER_OVERHEAD_SECONDS: f32 = 15.0;
+// Battles that take longer than this are considered stalled and count as losses
+const MAX_ROUNDS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+struct Options {
+    battles: usize,
+    levels: Vec<i32>,
+    seed: u64,
+    format: OutputFormat,
+}
+
+struct MatchupStats {
+    class: CharacterClass,
+    anomaly: AnomalyDefinition,
+    level: i32,
+    battles: usize,
+    wins: usize,
+    rounds: usize,
+    seconds: f32,
+    xp: i32,
+    gold: i32,
+}
+
+impl MatchupStats {
+    fn win_rate(&self) -> f32 {
+        self.wins as f32 / self.battles as f32
+    }
+
+    fn average_rounds(&self) -> f32 {
+        self.rounds as f32 / self.battles as f32
+    }
+
+    fn xp_per_minute(&self) -> f32 {
+        self.xp as f32 / (self.seconds / 60.0)
+    }
+
+    fn gold_per_minute(&self) -> f32 {
+        self.gold as f32 / (self.seconds / 60.0)
+    }
+}
+
+fn parse_options() -> Result<Options, DynamicError> {
+    let mut options = Options {
+        battles: 1000,
+        levels: vec![1, 5, 10, 15, 20, 30],
+        seed: rand::random(),
+        format: OutputFormat::Csv,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or(format!("Missing value for {}", arg))?;
+
+        match arg.as_str() {
+            "--battles" => options.battles = value.parse()?,
+            "--seed" => options.seed = value.parse()?,
+            "--levels" => {
+                options.levels = value
+                    .split(',')
+                    .map(|l| l.trim().parse())
+                    .collect::<Result<Vec<i32>, _>>()?
+            }
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    _ => Err(format!("Invalid format {}", value))?,
+                }
+            }
+            _ => Err(format!("Unknown option {}", arg))?,
+        }
+    }
+
+    if options.battles == 0 {
+        Err("At least one battle is needed")?;
+    }
+
+    Ok(options)
+}
+
+// Levels a fresh character the same way /aventura does
+fn create_user_data(rng: &mut impl Rng, class: CharacterClass, level: i32) -> UserData {
+    let mut user_data = UserData::new_with_class(String::new(), class);
+
+    while user_data.level < level {
+        user_data.add_xp(math::calculate_xp_required_for_level_up(user_data.level));
+        user_data.level_up(rng);
+    }
+
+    spend_attribute_points(rng, &mut user_data);
+
+    user_data
+}
+
+// Spends the points the way level ups used to before players chose them, mostly on health and mana
+fn spend_attribute_points(rng: &mut impl Rng, user_data: &mut UserData) {
+    const COMMON: [Attribute; 2] = [Attribute::Health, Attribute::Mana];
+    const RARE: [Attribute; 3] = [
+        Attribute::Strength,
+        Attribute::Intelligence,
+        Attribute::Agility,
+    ];
+
+    while user_data.attribute_points > 0 {
+        let attributes: &[Attribute] = if rng.gen_bool(0.7) { &COMMON } else { &RARE };
+
+        let Some(&attribute) = attributes.choose(rng) else {
+            break;
+        };
+
+        user_data.spend_attribute_points(attribute, 1);
+    }
+}
+
+// Generated characters have no equipment, talents or items, only what their class gives
+fn player_stats(class: CharacterClass, user_data: &UserData) -> PlayerStats {
+    PlayerStats {
+        health: user_data.health,
+        mana: user_data.mana,
+        strength: user_data.strength,
+        agility: user_data.agility,
+        intelligence: user_data.intelligence,
+        skills: class.skills.to_vec(),
+        passives: vec![class.passive.effect],
+        items: Default::default(),
+    }
+}
+
+// A reasonable player: heals when low, uses the strongest affordable skill and attacks otherwise
+fn choose_player_action(fighter: &Fighter) -> ActionType {
+    let skills = fighter.available_skills();
+    let affordable = skills.iter().filter(|s| fighter.can_afford(s));
+
+    if fighter.health.percentage() < 40 {
+        if let Some(skill) = affordable.clone().find(|s| s.heals()) {
+            return ActionType::Skill(skill.skill_type);
+        }
+    }
+
+    affordable
+        .filter(|s| s.deals_damage())
+        .max_by_key(|s| s.mana_cost)
+        .map_or(ActionType::Attack, |s| ActionType::Skill(s.skill_type))
+}
+
+fn simulate_battle(
+    rng: &mut StdRng,
+    class: CharacterClass,
+    definition: &AnomalyDefinition,
+    level: i32,
+) -> Result<(bool, usize, f32, i32, i32), DynamicError> {
+    let user_data = create_user_data(rng, class, level);
+    let anomaly = anomalies::generate_anomaly_from_definition(rng, definition, level);
+
+    let fighters = vec![
+        Fighter::create_from_player(0, class.name, player_stats(class, &user_data)),
+        Fighter::create_from_anomaly(anomaly),
+    ];
+
+    let mut battle = Battle::new(fighters, rng.gen())?;
+    let mut seconds = ENCOUNTER_OVERHEAD_SECONDS;
+
+    while !battle.finished() && battle.rounds().len() < MAX_ROUNDS {
+        let is_player = battle.current_fighter().user_id.is_some();
+
+        if battle.current_fighter().is_stunned() {
+            battle.skip_turn();
+        } else if is_player {
+            let action = choose_player_action(battle.current_fighter());
+            battle.run_action(action)?;
+        } else {
+            let action = battle.choose_ai_action();
+            battle.run_action(action)?;
+        }
+
+        seconds += if is_player {
+            PLAYER_ROUND_SECONDS
+        } else {
+            ANOMALY_ROUND_SECONDS
+        };
+    }
+
+    let won = battle.winning_team() == Some(battle::PLAYER_TEAM);
+    let (xp, gold) = if won {
+        (anomaly.rewards.xp, anomaly.rewards.gold)
+    } else {
+        (0, 0)
+    };
+
+    Ok((won, battle.rounds().len(), seconds, xp, gold))
+}
+
+fn simulate_matchup(
+    rng: &mut StdRng,
+    class: CharacterClass,
+    anomaly: AnomalyDefinition,
+    level: i32,
+    battles: usize,
+) -> Result<MatchupStats, DynamicError> {
+    let mut stats = MatchupStats {
+        class,
+        anomaly,
+        level,
+        battles,
+        wins: 0,
+        rounds: 0,
+        seconds: 0.0,
+        xp: 0,
+        gold: 0,
+    };
+
+    for _ in 0..battles {
+        let (won, rounds, seconds, xp, gold) = simulate_battle(rng, class, &anomaly, level)?;
+
+        stats.wins += won as usize;
+        stats.rounds += rounds;
+        stats.seconds += seconds;
+        stats.xp += xp;
+        stats.gold += gold;
+    }
+
+    Ok(stats)
+}
+
+fn print_csv(results: &[MatchupStats]) {
+    println!("class,anomaly,level,battles,win_rate,average_rounds,xp_per_minute,gold_per_minute");
+
+    for r in results {
+        println!(
+            "{:?},{:?},{},{},{:.3},{:.2},{:.2},{:.2}",
+            r.class.class_type,
+            r.anomaly.anomaly_type,
+            r.level,
+            r.battles,
+            r.win_rate(),
+            r.average_rounds(),
+            r.xp_per_minute(),
+            r.gold_per_minute()
+        );
+    }
+}
+
+fn print_json(results: &[MatchupStats], seed: u64) {
+    let entries = results
+        .iter()
+        .map(|r| {
+            format!(
+                "    {{\"class\": \"{:?}\", \"anomaly\": \"{:?}\", \"level\": {}, \"battles\": {}, \"winRate\": {:.3}, \"averageRounds\": {:.2}, \"xpPerMinute\": {:.2}, \"goldPerMinute\": {:.2}}}",
+                r.class.class_type,
+                r.anomaly.anomaly_type,
+                r.level,
+                r.battles,
+                r.win_rate(),
+                r.average_rounds(),
+                r.xp_per_minute(),
+                r.gold_per_minute()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",\n");
+
+    println!(
+        "{{\n  \"seed\": {},\n  \"results\": [\n{}\n  ]\n}}",
+        seed, entries
+    );
+}
+
+fn main() -> Result<(), DynamicError> {
+    let options = parse_options()?;
+    let rng = &mut StdRng::seed_from_u64(options.seed);
+
+    let mut results = Vec::new();
+    for class in ALL_CLASSES {
+        for anomaly in ANOMALIES {
+            for &level in &options.levels {
+                results.push(simulate_matchup(
+                    rng,
+                    class,
+                    anomaly,
+                    level,
+                    options.battles,
+                )?);
+            }
+        }
+    }
+
+    match options.format {
+        OutputFormat::Csv => {
+            // Keeps the output reproducible with --seed
+            eprintln!("seed: {}", options.seed);
+            print_csv(&results);
+        }
+        OutputFormat::Json => print_json(&results, options.seed),
+    }
+
+    Ok(())
+}
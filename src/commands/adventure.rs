@@ -1,12 +1,33 @@
 use std::time::Duration;
 
-use crate::battle::{self, Fighter};
-use data::{anomalies, regions::RegionType};
-use database::user_model::Region;
-use rand::{thread_rng, Rng};
+use tokio_stream::StreamExt;
+use twilight_model::{
+    application::interaction::InteractionData,
+    channel::message::{component::ButtonStyle, ReactionType},
+    user::User,
+};
+
+use crate::battle::{self, Fighter, PLAYER_TEAM};
+use data::{
+    anomalies::{self, Anomaly, AnomalyDrops},
+    bosses,
+    items::get_item_by_type,
+    regions::RegionType,
+};
+use database::user_model::{EncounterResult, Region, UserData};
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 
 use super::prelude::*;
 
+// Fraction of the player's gold lost when defeated by an anomaly
+const DEFEAT_GOLD_PENALTY: f32 = 0.1;
+
+const MAX_PARTY_SIZE: usize = 4;
+
+const JOIN_BUTTON_ID: &str = "join";
+const FIGHT_BUTTON_ID: &str = "fight";
+const DECLINE_BUTTON_ID: &str = "decline";
+
 pub struct AdventureCommand;
 
 #[async_trait]
@@ -26,14 +47,14 @@ impl Command for AdventureCommand {
     async fn run(&self, mut ctx: CommandContext) -> CommandResult {
         let author = ctx.author().await?;
         let author_id = author.id;
-        let mut author_data = ctx
+        let author_data = ctx
             .db()
             .get_user_data(&author.id.to_string())
             .await?
             .ok_or("Invalid data")?;
 
         if author_data.health.value < 15 {
-            return ctx.reply(Response::new_user_reply(author, "você está sem vida para batalhar! Use **/descansar** antes de partir para uma nova aventura.").error_response()).await;
+            return ctx.reply(Response::new_user_reply(author, "você está sem vida para batalhar! Descanse com **/descansar** ou espere sua vida se recuperar com o tempo.").error_response()).await;
         }
 
         if author_data.journey.current_region.region_type == RegionType::City {
@@ -52,12 +73,38 @@ impl Command for AdventureCommand {
                 return Ok(());
             }
 
-            let new_region = Region::generate_random_from_journey(author_data.journey.clone());
+            // The confirmation may take a while to be answered
+            let original = ctx
+                .db()
+                .get_user_data(&author.id.to_string())
+                .await?
+                .ok_or("Invalid data")?;
+            let mut author_data = original.clone();
+
+            let new_region = Region::generate_random_from_journey(
+                &mut thread_rng(),
+                author_data.journey.clone(),
+            );
 
             author_data.travel_distance(thread_rng().gen_range(0.65..0.7));
             author_data.travel_to_region(new_region.clone());
 
-            ctx.db().save_user_data(author_data).await?;
+            if !ctx
+                .db()
+                .save_user_data_changes(&original, author_data)
+                .await?
+            {
+                ctx.send_in_channel(
+                    Response::new_user_reply(
+                        author,
+                        "não foi possível sair da sua cidade, tente novamente!",
+                    )
+                    .error_response(),
+                )
+                .await?;
+
+                return Ok(());
+            }
 
             ctx.send_in_channel(
                 Response::new_user_reply(
@@ -74,103 +121,440 @@ impl Command for AdventureCommand {
             return Ok(());
         }
 
-        let author_fighter = Fighter::create_from_user_data(author.clone(), author_data.clone())?;
+        // A single seed generates the anomalies, the battle seed, the loot and the travel, so
+        // encounters can be reproduced
+        let seed: u64 = thread_rng().gen();
+        let rng = &mut StdRng::seed_from_u64(seed);
+
+        // Reaching the end of a region means facing its boss before moving on
+        let boss = if author_data.journey.boss_pending {
+            bosses::get_region_boss(author_data.journey.current_region.region_type)
+        } else {
+            None
+        };
+
+        let mut party = vec![(author.clone(), author_data.clone())];
+        let mut anomalies = vec![match &boss {
+            Some(boss) => bosses::generate_boss(rng, boss, author_data.level),
+            None => anomalies::generate_random_anomaly(
+                rng,
+                author_data.level,
+                author_data.journey.current_region.region_type,
+            ),
+        }];
+
+        let message = ctx
+            .send(get_lobby_response(&author, &party, &anomalies, true))
+            .await?;
 
-        let anomaly = anomalies::generate_random_anomaly(
-            author_data.level,
-            author_data.journey.current_region.region_type,
+        let standby = ctx.standby.clone();
+        let mut stream = standby.create_component_stream(
+            message.id,
+            Duration::from_secs(120),
+            |_: &Interaction| true,
         );
-        let anomaly_fighter = Fighter::create_from_anomaly(anomaly)?;
-
-        let embed = EmbedBuilder::new()
-            .set_author(EmbedAuthor {
-                name: f!("{} encontrou uma anomalia!", author.name),
-                icon_url: Some(author.avatar_url()),
-            })
-            .add_field(EmbedField {
-                name: f!("{} (nível {})", anomaly.name(), anomaly.level),
-                value: f!("{}", anomaly_fighter.display_full_stats()),
-                inline: false,
-            })
-            .set_description(f!("Se vencer, você receberá:\n{}", anomaly.rewards))
-            .set_image(anomaly.image())
-            .set_color(Color::YELLOW)
-            .set_current_timestamp();
-
-        let confirmation = ctx
-            .create_confirmation(
-                author.clone(),
-                Response {
-                    embeds: Some(vec![embed]),
-                    ..Response::new_user_reply(
-                        author.clone(),
-                        "você encontrou uma anomalia. Quer enfrentá-la?",
+
+        let mut started = false;
+        while let Some(Ok(component)) = stream.next().await {
+            let Some(InteractionData::MessageComponent(data)) = &component.data else {
+                continue;
+            };
+
+            let user = component
+                .member
+                .as_ref()
+                .and_then(|m| m.user.clone())
+                .or_else(|| component.user.clone());
+            let Some(user) = user else {
+                continue;
+            };
+
+            let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+
+            match data.custom_id.as_str() {
+                FIGHT_BUTTON_ID | DECLINE_BUTTON_ID if user.id == author_id => {
+                    started = data.custom_id == FIGHT_BUTTON_ID;
+                    ctx.update_interaction(
+                        get_lobby_response(&author, &party, &anomalies, false)
+                            .remove_all_components(),
                     )
-                },
-            )
-            .await;
+                    .await
+                    .ok();
+                    break;
+                }
+                JOIN_BUTTON_ID
+                    if party.len() < MAX_PARTY_SIZE
+                        && party.iter().all(|(u, _)| u.id != user.id) =>
+                {
+                    let user_data = ctx.db().get_user_data(&user.id.to_string()).await?;
+
+                    if let Some(user_data) = user_data.filter(|d| d.health.value >= 15) {
+                        // Bosses face the whole party alone
+                        if boss.is_none() {
+                            anomalies.push(anomalies::generate_random_anomaly(
+                                rng,
+                                user_data.level,
+                                author_data.journey.current_region.region_type,
+                            ));
+                        }
+
+                        party.push((user, user_data));
+                    }
+                }
+                _ => {}
+            }
 
-        if !confirmation {
-            return Ok(());
+            ctx.update_interaction(get_lobby_response(
+                &author,
+                &party,
+                &anomalies,
+                party.len() < MAX_PARTY_SIZE,
+            ))
+            .await
+            .ok();
         }
 
-        let fighters = vec![author_fighter, anomaly_fighter];
+        if !started {
+            return Ok(());
+        }
 
-        let battle = &mut battle::Battle::new(fighters)?;
+        let mut fighters = party
+            .iter()
+            .map(|(user, data)| battle::create_fighter_from_user(user, data.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let battle_result = battle::controller::handle_battle(&ctx, battle).await?;
+        for anomaly in &anomalies {
+            fighters.push(Fighter::create_from_anomaly(*anomaly));
+        }
 
-        let winner = battle_result.winner;
-        let author_fighter = battle_result
-            .all_fighters
-            .iter()
-            .find(|f| f.user.as_ref().map_or(false, |u| u.id == author_id))
-            .ok_or("Author fighter not found")?;
+        let battle = &mut battle::Battle::new(fighters, rng.gen::<u64>())?;
 
-        if winner.user.is_some() {
-            let distance = thread_rng().gen_range(0.2..0.4) as f32;
-            author_data.travel_distance(distance);
+        let battle_result = battle::controller::handle_battle(&ctx, battle).await?;
 
-            author_data.add_gold(anomaly.rewards.gold);
-            author_data.add_xp(anomaly.rewards.xp);
-            let new_level = author_data.level_up();
+        let rewards = split_rewards(&anomalies, party.len());
+        let region_name = author_data.journey.current_region.name.clone();
 
-            let mut response =
-                Response::new_user_reply(author.clone(), f!("você recebeu:\n{}", anomaly.rewards))
-                    .set_emoji_prefix("💰");
+        // Each dropped item goes to a random member of the party
+        let mut party_loot = vec![Vec::new(); party.len()];
+        if battle_result.winning_team == Some(PLAYER_TEAM) {
+            for anomaly in &anomalies {
+                for drop in anomaly.roll_loot(rng) {
+                    party_loot[rng.gen_range(0..party.len())].push(drop);
+                }
+            }
+        }
 
-            if let Some(level) = new_level {
-                response = response
-                    .add_string_content(f!("\n🌀 **|** Você agora está no nível **{}**", level));
+        for ((user, _), loot) in party.into_iter().zip(party_loot) {
+            let fighter = battle_result
+                .all_fighters
+                .iter()
+                .find(|f| f.user_id == Some(user.id.get()))
+                .ok_or("Party fighter not found")?;
+
+            // The data loaded when joining is minutes old by now
+            let original = ctx
+                .db()
+                .get_user_data(&user.id.to_string())
+                .await?
+                .ok_or("Invalid data")?;
+            let mut user_data = original.clone();
+
+            // Consumables are spent before the loot is added so their space can be reused
+            battle::remove_used_items(fighter, &mut user_data);
+
+            // A member fleeing ends the battle for the whole party, nobody wins or loses
+            let result = if battle_result.fled_fighter.is_some() {
+                EncounterResult::Fled
+            } else if battle_result.winning_team == Some(PLAYER_TEAM) {
+                EncounterResult::Won
+            } else {
+                EncounterResult::Lost
+            };
+
+            let response = match result {
+                EncounterResult::Won => {
+                    user_data.add_gold(rewards.gold);
+                    user_data.add_xp(rewards.xp);
+                    let new_level = user_data.level_up(rng);
+
+                    let mut response =
+                        Response::new_user_reply(user.clone(), f!("você recebeu:\n{}", rewards))
+                            .set_emoji_prefix("💰");
+
+                    for (item_type, amount) in loot {
+                        let Some(item) = get_item_by_type(item_type) else {
+                            continue;
+                        };
+
+                        let added = user_data.add_item(item_type, amount);
+                        response = response.add_string_content(if added < amount {
+                            f!(
+                                "\n{} **{}x {}** (sem espaço para mais {})",
+                                item.emoji,
+                                added,
+                                item.name,
+                                amount - added
+                            )
+                        } else {
+                            f!("\n{} **{}x {}**", item.emoji, added, item.name)
+                        });
+                    }
+
+                    if let Some(level) = new_level {
+                        response = response.add_string_content(f!(
+                            "\n🌀 **|** Você agora está no nível **{}**! Distribua seus **{}** pontos de atributo com **/atributos** e seus **{}** pontos de talento com **/talentos**",
+                            level,
+                            user_data.attribute_points,
+                            user_data.skill_points()
+                        ));
+                    }
+
+                    // Members knocked out before the end don't get credit for the boss
+                    let survived = battle_result
+                        .survivors
+                        .iter()
+                        .any(|f| f.user_id == Some(user.id.get()));
+
+                    if let Some(boss) = boss.as_ref().filter(|_| survived) {
+                        user_data.add_boss_kill(boss.anomaly.anomaly_type, region_name.clone());
+
+                        response = response.add_string_content(f!(
+                            "\n👑 **|** Você derrotou **{}**, o chefe de **{}**!",
+                            boss.name(),
+                            region_name
+                        ));
+                    }
+
+                    // Only the party leader keeps walking through the region, and only leaves the
+                    // boss behind if they were still standing to defeat it
+                    if user.id == author_id {
+                        response = walk_through_region(
+                            rng,
+                            &mut user_data,
+                            boss.is_some() && survived,
+                            response,
+                        );
+                    }
+
+                    response
+                }
+                EncounterResult::Fled => {
+                    let fled = battle_result
+                        .fled_fighter
+                        .as_ref()
+                        .filter(|f| f.user_id != Some(user.id.get()));
+
+                    Response::new_user_reply(
+                        user.clone(),
+                        match fled {
+                            Some(fled) => f!(
+                                "**{}** fugiu da anomalia e o grupo não recebeu nenhuma recompensa.",
+                                fled.name
+                            ),
+                            None => "você fugiu da anomalia e não recebeu nenhuma recompensa."
+                                .into(),
+                        },
+                    )
+                    .set_emoji_prefix("🏃")
+                }
+                EncounterResult::Lost => {
+                    let lost_gold = (user_data.gold as f32 * DEFEAT_GOLD_PENALTY) as i32;
+                    user_data.remove_gold(lost_gold);
+
+                    Response::new_user_reply(
+                        user.clone(),
+                        f!(
+                            "você foi derrotado e deixou **{}** {} para trás enquanto fugia.",
+                            lost_gold,
+                            Emoji::Gold
+                        ),
+                    )
+                    .set_emoji_prefix("💀")
+                }
+            };
+
+            user_data.set_health_after_battle(fighter.health.value);
+            user_data.set_mana_after_battle(fighter.mana.value);
+            for anomaly in &anomalies {
+                user_data.try_add_to_bestiary(anomaly.anomaly_type, anomaly.variant, result);
             }
 
-            if author_data.journey.region_history.len() == 0
-                || author_data.journey.total_traveled
-                    > (author_data.journey.current_region.distance
-                        + thread_rng().gen_range(0.8..1.2))
+            // The consumables used in battle may have been spent elsewhere in the meantime
+            let response = if ctx
+                .db()
+                .save_user_data_changes(&original, user_data)
+                .await?
             {
-                let new_region = Region::generate_random_from_journey(author_data.journey.clone());
-
-                author_data.travel_to_region(new_region.clone());
-
-                response = response.add_string_content(f!(
-                    "\n🗺️ **|** Você vagou e chegou em **{}**!",
-                    new_region.name
-                ));
-            }
+                response
+            } else {
+                Response::new_user_reply(
+                    user.clone(),
+                    "você não tem mais os itens que usou na batalha, então o resultado não foi salvo!",
+                )
+                .error_response()
+            };
 
-            let ctx = ctx.clone();
+            let channel_ctx = ctx.clone();
             set_tokio_timeout(Duration::from_secs(2), async move {
-                ctx.send_in_channel(response).await.ok();
+                channel_ctx.send_in_channel(response).await.ok();
             });
         }
 
-        author_data.set_health(author_fighter.health.value);
-        author_data.set_mana(author_fighter.mana.value);
-        author_data.try_add_to_bestiary(anomaly.anomaly_type, winner.user.is_some());
+        Ok(())
+    }
+}
+
+fn walk_through_region(
+    rng: &mut impl Rng,
+    user_data: &mut UserData,
+    defeated_boss: bool,
+    response: Response,
+) -> Response {
+    if !defeated_boss {
+        let distance = rng.gen_range(0.2..0.4) as f32;
+        user_data.travel_distance(distance);
+
+        let reached_end = user_data.journey.region_history.is_empty()
+            || user_data.journey.total_traveled
+                > (user_data.journey.current_region.distance + rng.gen_range(0.8..1.2));
+
+        if !reached_end {
+            return response;
+        }
+
+        // Nobody guards the way out of the starting region
+        let region_boss = if user_data.journey.region_history.is_empty() {
+            None
+        } else {
+            bosses::get_region_boss(user_data.journey.current_region.region_type)
+        };
+
+        if let Some(region_boss) = region_boss {
+            user_data.journey.boss_pending = true;
+
+            return response.add_string_content(f!(
+                "\n👑 **|** Você chegou ao fim de **{}**, mas **{}** bloqueia o caminho! Derrote-o na sua próxima **/aventura** para seguir sua jornada.",
+                user_data.journey.current_region.name,
+                region_boss.name()
+            ));
+        }
+    }
 
-        ctx.db().save_user_data(author_data).await?;
+    let new_region = Region::generate_random_from_journey(rng, user_data.journey.clone());
+    user_data.travel_to_region(new_region.clone());
 
-        Ok(())
+    response.add_string_content(f!(
+        "\n🗺️ **|** Você vagou e chegou em **{}**!",
+        new_region.name
+    ))
+}
+
+// Rewards from every anomaly are split evenly between the party
+fn split_rewards(anomalies: &[Anomaly], party_size: usize) -> AnomalyDrops {
+    let party_size = party_size as i32;
+
+    AnomalyDrops {
+        xp: anomalies.iter().map(|a| a.rewards.xp).sum::<i32>() / party_size,
+        gold: anomalies.iter().map(|a| a.rewards.gold).sum::<i32>() / party_size,
+    }
+}
+
+fn get_lobby_response(
+    author: &User,
+    party: &[(User, UserData)],
+    anomalies: &[Anomaly],
+    can_join: bool,
+) -> Response {
+    let boss = bosses::get_boss_by_type(anomalies[0].anomaly_type);
+
+    let mut embed = EmbedBuilder::new()
+        .set_author(EmbedAuthor {
+            name: match boss {
+                Some(_) => f!("{} encontrou o chefe da região!", author.name),
+                None => f!("{} encontrou uma anomalia!", author.name),
+            },
+            icon_url: Some(author.avatar_url()),
+        })
+        .set_image(anomalies[0].image())
+        .set_color(Color::YELLOW)
+        .set_current_timestamp();
+
+    for anomaly in anomalies {
+        let mut fighter_stats = Fighter::create_from_anomaly(*anomaly).display_full_stats();
+
+        if let Some(variant) = anomaly.variant {
+            fighter_stats.push_str(&f!("\n👻 {}", variant.description()));
+        }
+
+        if !anomaly.definition.loot.is_empty() {
+            fighter_stats.push_str(&f!("\n🎁 Pode deixar: {}", anomaly.display_loot()));
+        }
+
+        embed = embed.add_field(EmbedField {
+            name: f!("{} (nível {})", anomaly.name(), anomaly.level),
+            value: fighter_stats,
+            inline: false,
+        });
+    }
+
+    let members = party
+        .iter()
+        .map(|(user, data)| f!("**{}** (nível {})", user.name, data.level))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let join_hint = match boss {
+        Some(boss) => f!(
+            "👑 **{}** guarda a saída desta região e precisa ser derrotado para seguir viagem! Outros aventureiros podem entrar no grupo (até **{}**) para enfrentá-lo juntos, e as recompensas serão divididas entre todos.",
+            boss.name(),
+            MAX_PARTY_SIZE
+        ),
+        None => f!(
+            "Outros aventureiros podem entrar no grupo (até **{}**). Cada um que entrar atrai mais uma anomalia, e as recompensas serão divididas entre todos.",
+            MAX_PARTY_SIZE
+        ),
+    };
+
+    embed = embed.set_description(f!(
+        "{}\n\nSe vencerem, cada um receberá:\n{}\n\n👥 **Grupo**:\n{}",
+        join_hint,
+        split_rewards(anomalies, party.len()),
+        members
+    ));
+
+    let buttons = ActionRowBuilder::new()
+        .add_button(
+            ButtonBuilder::new()
+                .set_label("Entrar")
+                .set_custom_id(JOIN_BUTTON_ID)
+                .set_disabled(!can_join)
+                .set_emoji(ReactionType::Unicode {
+                    name: "🤝".into()
+                }),
+        )
+        .add_button(
+            ButtonBuilder::new()
+                .set_label("Enfrentar")
+                .set_custom_id(FIGHT_BUTTON_ID)
+                .set_style(ButtonStyle::Danger)
+                .set_emoji(ReactionType::Unicode {
+                    name: "⚔️".into()
+                }),
+        )
+        .add_button(
+            ButtonBuilder::new()
+                .set_label("Recusar")
+                .set_custom_id(DECLINE_BUTTON_ID)
+                .set_emoji(ReactionType::Unicode { name: "❌".into() }),
+        )
+        .build();
+
+    Response {
+        embeds: Some(vec![embed]),
+        components: Some(vec![buttons]),
+        ..Response::new_user_reply(
+            author.clone(),
+            "você encontrou uma anomalia. Quer enfrentá-la?",
+        )
     }
 }
@@ -0,0 +1,302 @@
+use std::time::Duration;
+
+use data::classes::{get_class_by_type, ALL_ATTRIBUTES};
+use database::user_model::UserData;
+use tokio_stream::StreamExt;
+use twilight_model::{
+    application::interaction::InteractionData,
+    channel::message::{component::ButtonStyle, Component, ReactionType},
+    user::User,
+};
+
+use super::prelude::*;
+
+const RESPEC_COST_PER_LEVEL: i32 = 50;
+const RESPEC_BUTTON_ID: &str = "respec";
+
+fn respec_cost(data: &UserData) -> i32 {
+    data.level * RESPEC_COST_PER_LEVEL
+}
+
+fn get_attributes_response(user: &User, data: &UserData, amount: i32, active: bool) -> Response {
+    let mut embed = EmbedBuilder::new()
+        .set_author(EmbedAuthor {
+            name: f!("Atributos de {}", user.name),
+            icon_url: Some(user.avatar_url()),
+        })
+        .set_color(Color::ORANGE)
+        .set_description(f!(
+            "Você tem **{}** pontos para distribuir. Cada clique gasta **{}** ponto(s).\nRedistribuir custa **{}** {} e devolve todos os pontos gastos.",
+            data.attribute_points,
+            amount,
+            respec_cost(data),
+            Emoji::Gold
+        ))
+        .set_current_timestamp();
+
+    for attribute in ALL_ATTRIBUTES {
+        embed = embed.add_field(EmbedField {
+            name: f!("{} {}", attribute.emoji(), attribute.name()),
+            value: f!("**{}**", data.attribute(attribute)),
+            inline: true,
+        });
+    }
+
+    let response = Response::from_embeds(vec![embed]);
+    if !active {
+        return response.remove_all_components();
+    }
+
+    Response {
+        components: Some(get_attributes_components(data, amount)),
+        ..response
+    }
+}
+
+fn get_attributes_components(data: &UserData, amount: i32) -> Vec<Component> {
+    let buttons: Vec<ButtonBuilder> = ALL_ATTRIBUTES
+        .iter()
+        .enumerate()
+        .map(|(i, attribute)| {
+            ButtonBuilder::new()
+                .set_custom_id(i.to_string())
+                .set_emoji(ReactionType::Unicode {
+                    name: attribute.emoji().into(),
+                })
+                .set_label(f!("+{} {}", amount, attribute.name()))
+                .set_disabled(data.attribute_points < amount)
+        })
+        .collect();
+
+    vec![
+        ActionRowBuilder::new().add_buttons(buttons).build(),
+        ActionRowBuilder::new()
+            .add_button(
+                ButtonBuilder::new()
+                    .set_custom_id(RESPEC_BUTTON_ID)
+                    .set_emoji(ReactionType::Unicode {
+                        name: "🔄".into()
+                    })
+                    .set_label("Redistribuir")
+                    .set_style(ButtonStyle::Danger)
+                    .set_disabled(data.gold < respec_cost(data)),
+            )
+            .build(),
+    ]
+}
+
+pub struct AttributesCommand;
+
+#[async_trait]
+impl Command for AttributesCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "atributos",
+            "Distribua os pontos de atributo ganhos ao subir de nível",
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "quantidade",
+                "Quantos pontos gastar a cada clique",
+                CommandOptionType::Integer,
+            )
+            .set_required(false),
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+        let author_key = author.id.to_string();
+
+        let amount = ctx.options().get_integer("quantidade").unwrap_or(1);
+        let Ok(amount) = i32::try_from(amount) else {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "a quantidade é grande demais!")
+                        .error_response(),
+                )
+                .await;
+        };
+        if amount < 1 {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "a quantidade precisa ser maior que zero!")
+                        .error_response(),
+                )
+                .await;
+        }
+
+        let author_data = ctx
+            .db()
+            .get_user_data(&author_key)
+            .await?
+            .ok_or("Invalid data")?;
+
+        let message = ctx
+            .send(get_attributes_response(&author, &author_data, amount, true))
+            .await?;
+
+        let standby = ctx.standby.clone();
+        let mut stream = standby.create_component_stream(
+            message.id,
+            Duration::from_secs(120),
+            move |event: &Interaction| event.author_id() == Some(author_id),
+        );
+
+        let mut respec = false;
+        while let Some(Ok(component)) = stream.next().await {
+            let Some(InteractionData::MessageComponent(data)) = &component.data else {
+                break;
+            };
+
+            let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+
+            // Fetched again on every click, so nothing done elsewhere in the meantime is overwritten
+            let original = ctx
+                .db()
+                .get_user_data(&author_key)
+                .await?
+                .ok_or("Invalid data")?;
+            let mut author_data = original.clone();
+
+            if data.custom_id == RESPEC_BUTTON_ID {
+                ctx.update_interaction(get_attributes_response(
+                    &author,
+                    &author_data,
+                    amount,
+                    false,
+                ))
+                .await
+                .ok();
+                respec = true;
+                break;
+            }
+
+            let attribute = data
+                .custom_id
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| ALL_ATTRIBUTES.get(i).copied());
+
+            if let Some(attribute) = attribute {
+                if author_data.spend_attribute_points(attribute, amount)
+                    && !ctx
+                        .db()
+                        .save_user_data_changes(&original, author_data.clone())
+                        .await?
+                {
+                    ctx.send_in_channel(
+                        Response::new_user_reply(
+                            author,
+                            "não foi possível salvar seus atributos, tente novamente!",
+                        )
+                        .error_response(),
+                    )
+                    .await?;
+
+                    return Ok(());
+                }
+            }
+
+            ctx.update_interaction(get_attributes_response(&author, &author_data, amount, true))
+                .await
+                .ok();
+        }
+
+        if !respec {
+            return Ok(());
+        }
+
+        let cost = respec_cost(&author_data);
+        let confirmation = ctx
+            .create_confirmation(
+                author.clone(),
+                Response::new_user_reply(
+                    author.clone(),
+                    f!(
+                        "você quer redistribuir seus atributos por **{}** {}? Eles voltarão aos valores base da sua classe e você receberá de volta todos os pontos gastos.",
+                        cost,
+                        Emoji::Gold
+                    ),
+                )
+                .set_emoji_prefix("🔄"),
+            )
+            .await;
+
+        if !confirmation {
+            return Ok(());
+        }
+
+        let original = ctx
+            .db()
+            .get_user_data(&author_key)
+            .await?
+            .ok_or("Invalid data")?;
+        let mut author_data = original.clone();
+
+        let cost = respec_cost(&author_data);
+        if author_data.gold < cost {
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!(
+                        "você precisa de **{}** {} para redistribuir seus atributos!",
+                        cost,
+                        Emoji::Gold
+                    ),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        let class = get_class_by_type(author_data.class).ok_or("Invalid class")?;
+        author_data.remove_gold(cost);
+        let refunded = author_data.reset_attributes(&class);
+
+        // The gold may have been spent elsewhere before this is saved
+        if !ctx
+            .db()
+            .save_user_data_changes(&original, author_data.clone())
+            .await?
+        {
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!(
+                        "você precisa de **{}** {} para redistribuir seus atributos!",
+                        cost,
+                        Emoji::Gold
+                    ),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.send_in_channel(
+            Response::new_user_reply(
+                author,
+                f!(
+                    "seus atributos foram redistribuídos! Você recebeu **{}** pontos de volta e agora tem **{}** para gastar com **/atributos**.",
+                    refunded,
+                    author_data.attribute_points
+                ),
+            )
+            .success_response(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -82,14 +82,49 @@ impl Command for BattleCommand {
             .await?
             .ok_or("Invalid data")?;
 
-        let fighters = vec![
-            battle::Fighter::create_from_user_data(author, author_data)?,
-            battle::Fighter::create_from_user_data(user, user_data)?,
-        ];
+        let author_fighter = battle::create_fighter_from_user(&author, author_data)?;
 
-        let battle = &mut battle::Battle::new(fighters)?;
+        // Each player fights on their own team
+        let mut user_fighter = battle::create_fighter_from_user(&user, user_data)?;
+        user_fighter.team = author_fighter.team + 1;
 
-        battle::controller::handle_battle(&ctx, battle).await?;
+        let fighters = vec![author_fighter, user_fighter];
+
+        let battle = &mut battle::Battle::new(fighters, rand::random())?;
+
+        let battle_result = battle::controller::handle_battle(&ctx, battle).await?;
+
+        // Friendly battles don't change health or mana, but the consumed items are gone
+        for fighter in battle_result
+            .all_fighters
+            .iter()
+            .filter(|f| !f.used_items.is_empty())
+        {
+            let Some(user_id) = fighter.user_id else {
+                continue;
+            };
+
+            let original = ctx
+                .db()
+                .get_user_data(&user_id.to_string())
+                .await?
+                .ok_or("Invalid data")?;
+            let mut data = original.clone();
+
+            battle::remove_used_items(fighter, &mut data);
+
+            // The consumables may have been spent elsewhere during the battle
+            if !ctx.db().save_user_data_changes(&original, data).await? {
+                ctx.send_in_channel(
+                    Response::from_string(f!(
+                        "**{}** não tem mais os itens que usou na batalha!",
+                        fighter.name
+                    ))
+                    .error_response(),
+                )
+                .await?;
+            }
+        }
 
         Ok(())
     }
@@ -43,10 +43,14 @@ impl Command for BestiaryCommand {
 
         let mut pages: Vec<EmbedBuilder> = Vec::new();
         for entry in author_data.bestiary {
-            let anomaly = get_anomaly_from_type(entry.anomaly).ok_or("Invalid anomaly")?;
-            let anomaly_fighter = Fighter::create_from_anomaly(anomaly)?;
+            let mut anomaly = get_anomaly_from_type(entry.anomaly).ok_or("Invalid anomaly")?;
+            if let Some(variant) = entry.variant {
+                anomaly.apply_variant(variant);
+            }
 
-            let embed = EmbedBuilder::new()
+            let anomaly_fighter = Fighter::create_from_anomaly(anomaly);
+
+            let mut embed = EmbedBuilder::new()
                 .set_author(EmbedAuthor {
                     name: f!("Bestiário de {}", author.name),
                     icon_url: Some(author.avatar_url()),
@@ -55,17 +59,45 @@ impl Command for BestiaryCommand {
                 .set_thumbnail(anomaly.image())
                 .set_title(f!("{}", anomaly.name()))
                 .set_description(f!(
-                    "Você derrotou essa anomalia **{}** vezes e foi derrotado **{}** vezes.",
+                    "Você derrotou essa anomalia **{}** vezes, foi derrotado **{}** vezes e fugiu **{}** vezes.",
                     entry.wins,
-                    entry.loses
+                    entry.loses,
+                    entry.flees
                 ))
                 .add_field(EmbedField {
                     name: f!("🟢 Atributos Base"),
                     value: f!("{}", anomaly_fighter.display_full_stats()),
                     inline: false,
                 })
+                .add_field(EmbedField {
+                    name: "🧠 Comportamento".to_string(),
+                    value: anomaly.definition.behavior.description().into(),
+                    inline: false,
+                })
+                .add_field(EmbedField {
+                    name: "✨ Habilidades".to_string(),
+                    value: anomaly_fighter.display_skills(),
+                    inline: false,
+                })
+                .add_field(EmbedField {
+                    name: "🎁 Itens".to_string(),
+                    value: if anomaly.definition.loot.is_empty() {
+                        "Nenhum".into()
+                    } else {
+                        anomaly.display_loot()
+                    },
+                    inline: false,
+                })
                 .set_current_timestamp();
 
+            if let Some(variant) = entry.variant {
+                embed = embed.add_field(EmbedField {
+                    name: "👻 Variante".to_string(),
+                    value: variant.description().into(),
+                    inline: false,
+                });
+            }
+
             pages.push(embed);
         }
 
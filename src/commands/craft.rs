@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use data::crafting::{Recipe, ALL_RECIPES};
+use twilight_model::{application::interaction::InteractionData, channel::message::ReactionType};
+
+use super::prelude::*;
+
+const RECIPE_SELECT_ID: &str = "recipe";
+
+pub struct CraftCommand;
+
+#[async_trait]
+impl Command for CraftCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "criar",
+            "Crie equipamentos e consumíveis com os materiais do seu inventário",
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "quantidade",
+                "Quantas vezes você quer usar a receita",
+                CommandOptionType::Integer,
+            )
+            .set_required(false),
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+        let author_data = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        let times = ctx.options().get_integer("quantidade").unwrap_or(1);
+        let Ok(times) = i32::try_from(times) else {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "a quantidade é grande demais!")
+                        .error_response(),
+                )
+                .await;
+        };
+        if times < 1 {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "a quantidade precisa ser maior que zero!")
+                        .error_response(),
+                )
+                .await;
+        }
+
+        let craftable: Vec<Recipe> = ALL_RECIPES
+            .iter()
+            .filter(|r| author_data.can_craft(r, times))
+            .copied()
+            .collect();
+
+        let mut embed = EmbedBuilder::new()
+            .set_author(EmbedAuthor {
+                name: f!("Receitas de {}", author.name),
+                icon_url: Some(author.avatar_url()),
+            })
+            .set_color(Color::ORANGE)
+            .set_description(if craftable.is_empty() {
+                f!(
+                    "Você não tem materiais para criar nenhum item **{}x**. Derrote anomalias para conseguir mais!",
+                    times
+                )
+            } else {
+                f!("Escolha uma receita para usar **{}x**.", times)
+            })
+            .set_current_timestamp();
+
+        for recipe in ALL_RECIPES {
+            let item = recipe.item();
+            let status = if craftable.contains(&recipe) {
+                "✅"
+            } else {
+                "❌"
+            };
+
+            embed = embed.add_field(EmbedField {
+                name: f!(
+                    "{} {} {} (x{})",
+                    status,
+                    item.emoji,
+                    item.name,
+                    recipe.amount
+                ),
+                value: recipe.display_ingredients(|i| author_data.item_amount(i)),
+                inline: true,
+            });
+        }
+
+        if craftable.is_empty() {
+            ctx.send(Response::from_embeds(vec![embed])).await?;
+            return Ok(());
+        }
+
+        let mut select_menu =
+            SelectMenuBuilder::new(RECIPE_SELECT_ID).set_placeholder("Escolha um item para criar");
+
+        for (i, recipe) in craftable.iter().enumerate() {
+            let item = recipe.item();
+
+            select_menu = select_menu.add_option(
+                f!("{} (x{})", item.name, recipe.amount * times),
+                i,
+                Some(
+                    item.display_effects()
+                        .replace("**", "")
+                        .chars()
+                        .take(100)
+                        .collect(),
+                ),
+                Some(ReactionType::Unicode {
+                    name: item.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        let response = Response {
+            embeds: Some(vec![embed]),
+            components: Some(vec![ActionRowBuilder::new()
+                .add_select_menu(select_menu)
+                .build()]),
+            ..Default::default()
+        };
+
+        let message = ctx.send(response.clone()).await?;
+
+        let standby = ctx.standby.clone();
+        let Ok(Some(component)) = standby.wait_for_component_with_duration(message.id, Duration::from_secs(60), move |event: &Interaction| {
+            event.author_id() == Some(author_id)
+        }).await else {
+            return Ok(());
+        };
+
+        let Some(InteractionData::MessageComponent(data)) = &component.data else {
+            return Ok(());
+        };
+
+        let recipe = data
+            .values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|i| craftable.get(i))
+            .ok_or("Invalid recipe")?;
+
+        let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+        ctx.update_interaction(response.remove_all_components())
+            .await
+            .ok();
+
+        let item = recipe.item();
+
+        // The materials are checked again by the database, they may have been spent meanwhile
+        if !ctx
+            .db()
+            .craft_item(&author.id.to_string(), recipe, times)
+            .await?
+        {
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!(
+                        "você não tem mais os materiais ou espaço para criar **{}**!",
+                        item.name
+                    ),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.send_in_channel(
+            Response::new_user_reply(
+                author,
+                f!(
+                    "você criou **{}x** {} **{}**!",
+                    recipe.amount * times,
+                    item.emoji,
+                    item.name
+                ),
+            )
+            .set_emoji_prefix("🔨"),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
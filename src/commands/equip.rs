@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use data::items::{get_item_by_type, EquipmentSlot, Item, ItemType, ALL_EQUIPMENT_SLOTS};
+use database::user_model::UserData;
+use twilight_model::{
+    application::interaction::InteractionData, channel::message::ReactionType, user::User,
+};
+
+use super::prelude::*;
+
+const EQUIPMENT_SELECT_ID: &str = "equipment";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EquipAction {
+    Equip(ItemType),
+    Unequip(EquipmentSlot),
+}
+
+pub struct EquipCommand;
+
+fn get_equipment_embed(author: &User, user_data: &UserData) -> EmbedBuilder {
+    let mut embed = EmbedBuilder::new()
+        .set_author(EmbedAuthor {
+            name: f!("Equipamento de {}", author.name),
+            icon_url: Some(author.avatar_url()),
+        })
+        .set_color(Color::ORANGE)
+        .set_current_timestamp();
+
+    for slot in ALL_EQUIPMENT_SLOTS {
+        let value = match user_data.equipment.get(slot).and_then(get_item_by_type) {
+            Some(item) => f!(
+                "{} **{}**\n{}",
+                item.emoji,
+                item.name,
+                item.equipment()
+                    .map(|(_, s)| s.to_string())
+                    .unwrap_or_default()
+            ),
+            None => "Nada equipado".into(),
+        };
+
+        embed = embed.add_field(EmbedField {
+            name: slot.name().into(),
+            value,
+            inline: true,
+        });
+    }
+
+    let mut bonus = user_data.equipment.stats().to_string();
+    if bonus.is_empty() {
+        bonus = "nenhum".into();
+    }
+
+    embed.set_description(f!("**Bônus total**: {}", bonus))
+}
+
+fn get_actions(user_data: &UserData) -> Vec<(EquipAction, Item)> {
+    let equip = user_data
+        .inventory
+        .keys()
+        .filter_map(|&item| get_item_by_type(item))
+        .filter(|item| item.equipment().is_some())
+        .map(|item| (EquipAction::Equip(item.item_type), item));
+
+    let unequip = ALL_EQUIPMENT_SLOTS.iter().filter_map(|&slot| {
+        let item = user_data.equipment.get(slot).and_then(get_item_by_type)?;
+        Some((EquipAction::Unequip(slot), item))
+    });
+
+    equip.chain(unequip).collect()
+}
+
+#[async_trait]
+impl Command for EquipCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "equipar",
+            "Equipe armas, armaduras e acessórios do seu inventário",
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+        let author_data = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        let actions = get_actions(&author_data);
+        let embed = get_equipment_embed(&author, &author_data);
+
+        if actions.is_empty() {
+            ctx.send(Response::from_embeds(vec![embed])).await?;
+            return Ok(());
+        }
+
+        let mut select_menu = SelectMenuBuilder::new(EQUIPMENT_SELECT_ID)
+            .set_placeholder("Escolha um item para equipar ou desequipar");
+
+        for (i, (action, item)) in actions.iter().enumerate() {
+            let label = match action {
+                EquipAction::Equip(_) => f!("Equipar {}", item.name),
+                EquipAction::Unequip(_) => f!("Desequipar {}", item.name),
+            };
+
+            select_menu = select_menu.add_option(
+                label,
+                i,
+                Some(
+                    item.display_effects()
+                        .replace("**", "")
+                        .chars()
+                        .take(100)
+                        .collect(),
+                ),
+                Some(ReactionType::Unicode {
+                    name: item.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        let response = Response {
+            embeds: Some(vec![embed]),
+            components: Some(vec![ActionRowBuilder::new()
+                .add_select_menu(select_menu)
+                .build()]),
+            ..Default::default()
+        };
+
+        let message = ctx.send(response.clone()).await?;
+
+        let standby = ctx.standby.clone();
+        let Ok(Some(component)) = standby.wait_for_component_with_duration(message.id, Duration::from_secs(60), move |event: &Interaction| {
+            event.author_id() == Some(author_id)
+        }).await else {
+            return Ok(());
+        };
+
+        let Some(InteractionData::MessageComponent(data)) = &component.data else {
+            return Ok(());
+        };
+
+        let (action, item) = data
+            .values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|i| actions.get(i))
+            .ok_or("Invalid equipment action")?;
+
+        let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+
+        // The inventory may have changed while the menu was open
+        let original = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+        let mut author_data = original.clone();
+
+        let success = match *action {
+            EquipAction::Equip(item) => author_data.equip(item),
+            EquipAction::Unequip(slot) => author_data.unequip(slot),
+        };
+
+        if !success {
+            ctx.update_interaction(response.remove_all_components())
+                .await
+                .ok();
+
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!(
+                        "não foi possível trocar **{}**, ele não está mais no seu inventário ou seu inventário está cheio!",
+                        item.name
+                    ),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        let embed = get_equipment_embed(&author, &author_data);
+
+        // The item may have been spent elsewhere before this is saved
+        if !ctx
+            .db()
+            .save_user_data_changes(&original, author_data)
+            .await?
+        {
+            ctx.update_interaction(response.remove_all_components())
+                .await
+                .ok();
+
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!(
+                        "não foi possível trocar **{}**, ele não está mais no seu inventário!",
+                        item.name
+                    ),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.update_interaction(Response::from_embeds(vec![embed]).remove_all_components())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use database::battle_log::BattleLog;
+use twilight_model::application::interaction::InteractionData;
+
+use crate::{battle::controller, discord::pagination::EmbedPagination};
+
+use super::prelude::*;
+
+const BATTLE_SELECT_ID: &str = "battle";
+const MAX_BATTLES: i64 = 10;
+
+pub struct HistoryCommand;
+
+fn describe_result(log: &BattleLog, user_id: &String) -> &'static str {
+    let Some(participant) = log.participant(user_id) else {
+        return "❔ Desconhecido";
+    };
+
+    if log.fled_fighter.is_some() {
+        "🏃 Fuga"
+    } else if log.winning_team == Some(participant.team) {
+        "🏆 Vitória"
+    } else {
+        "💀 Derrota"
+    }
+}
+
+fn describe_participants(log: &BattleLog) -> String {
+    let mut teams: Vec<i32> = log.participants.iter().map(|p| p.team).collect();
+    teams.sort();
+    teams.dedup();
+
+    teams
+        .iter()
+        .map(|&team| {
+            log.participants
+                .iter()
+                .filter(|p| p.team == team)
+                .map(|p| p.name.clone())
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+        .collect::<Vec<String>>()
+        .join(" vs ")
+}
+
+#[async_trait]
+impl Command for HistoryCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "historico",
+            "Veja e reveja suas batalhas mais recentes",
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id.to_string();
+
+        let logs = ctx
+            .db()
+            .get_user_battle_logs(&author_id, MAX_BATTLES)
+            .await?;
+
+        if logs.is_empty() {
+            ctx.send(
+                Response::new_user_reply(author, "você ainda não participou de nenhuma batalha!")
+                    .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        let mut select_menu = SelectMenuBuilder::new(BATTLE_SELECT_ID)
+            .set_placeholder("Escolha uma batalha para rever");
+        let mut description = Vec::new();
+
+        for (i, log) in logs.iter().enumerate() {
+            let result = describe_result(log, &author_id);
+            let participants = describe_participants(log);
+
+            description.push(f!(
+                "**`#{}`** {} — {} (<t:{}:R>)",
+                i + 1,
+                result,
+                participants,
+                log.created_at / 1000
+            ));
+
+            select_menu = select_menu.add_option(
+                f!("#{} {}", i + 1, result),
+                log.id.to_hex(),
+                Some(participants.chars().take(100).collect()),
+                None,
+                false,
+            );
+        }
+
+        let embed = EmbedBuilder::new()
+            .set_author(EmbedAuthor {
+                name: f!("Batalhas recentes de {}", author.name),
+                icon_url: Some(author.avatar_url()),
+            })
+            .set_color(Color::LIGHT_ORANGE)
+            .set_description(description.join("\n"))
+            .set_current_timestamp();
+
+        let response = Response {
+            embeds: Some(vec![embed]),
+            components: Some(vec![ActionRowBuilder::new()
+                .add_select_menu(select_menu)
+                .build()]),
+            ..Default::default()
+        };
+
+        let message = ctx.send(response.clone()).await?;
+
+        let standby = ctx.standby.clone();
+        let Ok(Some(component)) = standby.wait_for_component_with_duration(message.id, Duration::from_secs(60), move |event: &Interaction| {
+            event.author_id() == Some(author.id)
+        }).await else {
+            return Ok(());
+        };
+
+        let Some(InteractionData::MessageComponent(data)) = &component.data else {
+            return Ok(());
+        };
+
+        let log = data
+            .values
+            .first()
+            .and_then(|id| logs.iter().find(|l| &l.id.to_hex() == id))
+            .ok_or("Invalid battle")?;
+
+        let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+        ctx.update_interaction(response.remove_all_components())
+            .await
+            .ok();
+
+        let image = log
+            .winners()
+            .first()
+            .copied()
+            .or(log.participants.first())
+            .map(|p| p.image.clone())
+            .unwrap_or_default();
+
+        let mut pages =
+            controller::create_history_embeds(controller::render_battle_log(log)?, image);
+        for page in pages.iter_mut() {
+            page.add_footer_text(f!("Semente: {}", log.seed));
+        }
+
+        if pages.is_empty() {
+            Err("Battle has no rounds")?;
+        }
+
+        EmbedPagination::new(ctx, pages).send().await?;
+
+        Ok(())
+    }
+}
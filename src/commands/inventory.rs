@@ -0,0 +1,87 @@
+use data::items::get_item_by_type;
+
+use crate::discord::pagination::EmbedPagination;
+
+use super::prelude::*;
+
+const ITEMS_PER_PAGE: usize = 6;
+
+pub struct InventoryCommand;
+
+#[async_trait]
+impl Command for InventoryCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "inventario",
+            "Veja os itens que você carrega em sua jornada",
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_data = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        if author_data.inventory.is_empty() {
+            ctx.send(
+                Response::new_user_reply(author, "seu inventário está vazio!").error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        let items = author_data
+            .inventory
+            .iter()
+            .filter_map(|(&item, &amount)| get_item_by_type(item).map(|i| (i, amount)))
+            .collect::<Vec<_>>();
+
+        let mut pages: Vec<EmbedBuilder> = Vec::new();
+        for chunk in items.chunks(ITEMS_PER_PAGE) {
+            let mut embed = EmbedBuilder::new()
+                .set_author(EmbedAuthor {
+                    name: f!("Inventário de {}", author.name),
+                    icon_url: Some(author.avatar_url()),
+                })
+                .set_color(Color::ORANGE)
+                .set_description(f!("{} **Ouro**: {}", Emoji::Gold, author_data.gold))
+                .set_current_timestamp();
+
+            for (item, amount) in chunk {
+                embed = embed.add_field(EmbedField {
+                    name: f!(
+                        "{} {} ({}/{})",
+                        item.emoji,
+                        item.name,
+                        amount,
+                        item.max_stack
+                    ),
+                    value: f!(
+                        "{} {} • {}\n{}\n{}",
+                        item.rarity.emoji(),
+                        item.rarity.name(),
+                        item.kind.name(),
+                        item.description,
+                        item.display_effects()
+                    ),
+                    inline: false,
+                });
+            }
+
+            pages.push(embed);
+        }
+
+        EmbedPagination::new(ctx, pages).send().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use std::time::Duration;
+
+use data::items::{get_item_by_type, ItemKind, ItemType, ALL_ITEMS};
+use database::market::{Listing, ListingFilter, ListingKind};
+use tokio_stream::StreamExt;
+use twilight_model::{
+    application::interaction::InteractionData,
+    channel::message::{Component, ReactionType},
+};
+
+use super::prelude::*;
+
+const LISTINGS_PER_PAGE: i64 = 5;
+
+const KIND_SELECT_ID: &str = "kind";
+const CATEGORY_SELECT_ID: &str = "category";
+const LISTING_SELECT_ID: &str = "listing";
+const PREVIOUS_BUTTON_ID: &str = "previous";
+const NEXT_BUTTON_ID: &str = "next";
+
+const KIND_FILTERS: [(&str, Option<ListingKind>); 3] = [
+    ("Todos os anúncios", None),
+    ("Preço fixo", Some(ListingKind::FixedPrice)),
+    ("Leilões", Some(ListingKind::Auction)),
+];
+
+const CATEGORY_FILTERS: [&str; 4] = ["Todos os itens", "Consumíveis", "Equipamentos", "Materiais"];
+
+// None means every item
+fn category_items(category: usize) -> Option<Vec<ItemType>> {
+    let matches_category = |kind: &ItemKind| match category {
+        1 => matches!(kind, ItemKind::Consumable { .. }),
+        2 => matches!(kind, ItemKind::Equipment { .. }),
+        3 => matches!(kind, ItemKind::Material),
+        _ => true,
+    };
+
+    if category == 0 {
+        return None;
+    }
+
+    Some(
+        ALL_ITEMS
+            .iter()
+            .filter(|i| matches_category(&i.kind))
+            .map(|i| i.item_type)
+            .collect(),
+    )
+}
+
+fn describe_listing(listing: &Listing) -> String {
+    let price = match (&listing.kind, &listing.highest_bid) {
+        (ListingKind::FixedPrice, _) => f!("**{}** {}", listing.price, Emoji::Gold),
+        (ListingKind::Auction, Some(bid)) => {
+            f!(
+                "lance atual de **{}** {} por <@{}>",
+                bid.amount,
+                Emoji::Gold,
+                bid.user_id
+            )
+        }
+        (ListingKind::Auction, None) => {
+            f!("lance inicial de **{}** {}", listing.price, Emoji::Gold)
+        }
+    };
+
+    f!(
+        "{} • {} • vendido por <@{}> • termina <t:{}:R>",
+        listing.kind.name(),
+        price,
+        listing.seller_id,
+        listing.expires_at / 1000
+    )
+}
+
+struct MarketState {
+    kind: usize,
+    category: usize,
+    page: u64,
+    listings: Vec<Listing>,
+    total: u64,
+}
+
+impl MarketState {
+    fn filter(&self) -> ListingFilter {
+        ListingFilter {
+            kind: KIND_FILTERS[self.kind].1,
+            items: category_items(self.category),
+        }
+    }
+
+    fn pages(&self) -> u64 {
+        let per_page = LISTINGS_PER_PAGE as u64;
+        self.total.div_ceil(per_page).max(1)
+    }
+
+    async fn fetch(&mut self, ctx: &CommandContext) -> Result<(), DynamicError> {
+        let (listings, total) = ctx
+            .db()
+            .get_active_listings(&self.filter(), self.page, LISTINGS_PER_PAGE)
+            .await?;
+
+        self.listings = listings;
+        self.total = total;
+        Ok(())
+    }
+
+    fn response(&self, active: bool) -> Response {
+        let mut embed = EmbedBuilder::new()
+            .set_author(EmbedAuthor {
+                name: "Mercado".into(),
+                icon_url: None,
+            })
+            .set_color(Color::ORANGE)
+            .add_footer_text(f!(
+                "Página {} de {} • {} anúncios",
+                self.page + 1,
+                self.pages(),
+                self.total
+            ))
+            .set_current_timestamp();
+
+        if self.listings.is_empty() {
+            embed = embed
+                .set_description("Nenhum anúncio encontrado. Anuncie seus itens com **/vender**!");
+        }
+
+        for (i, listing) in self.listings.iter().enumerate() {
+            let Some(item) = get_item_by_type(listing.item) else {
+                continue;
+            };
+
+            embed = embed.add_field(EmbedField {
+                name: f!(
+                    "#{} {} {}x {}",
+                    i + 1,
+                    item.emoji,
+                    listing.amount,
+                    item.name
+                ),
+                value: describe_listing(listing),
+                inline: false,
+            });
+        }
+
+        let response = Response::from_embeds(vec![embed]);
+        if !active {
+            return response.remove_all_components();
+        }
+
+        Response {
+            components: Some(self.components()),
+            ..response
+        }
+    }
+
+    fn components(&self) -> Vec<Component> {
+        let mut kind_menu = SelectMenuBuilder::new(KIND_SELECT_ID);
+        for (i, (name, _)) in KIND_FILTERS.iter().enumerate() {
+            kind_menu = kind_menu.add_option(name, i, None, None, i == self.kind);
+        }
+
+        let mut category_menu = SelectMenuBuilder::new(CATEGORY_SELECT_ID);
+        for (i, name) in CATEGORY_FILTERS.iter().enumerate() {
+            category_menu = category_menu.add_option(name, i, None, None, i == self.category);
+        }
+
+        let mut components = vec![
+            ActionRowBuilder::new().add_select_menu(kind_menu).build(),
+            ActionRowBuilder::new()
+                .add_select_menu(category_menu)
+                .build(),
+        ];
+
+        if !self.listings.is_empty() {
+            let mut listing_menu = SelectMenuBuilder::new(LISTING_SELECT_ID)
+                .set_placeholder("Escolha um anúncio para comprar ou dar um lance");
+
+            for (i, listing) in self.listings.iter().enumerate() {
+                let Some(item) = get_item_by_type(listing.item) else {
+                    continue;
+                };
+
+                listing_menu = listing_menu.add_option(
+                    f!("#{} {}x {}", i + 1, listing.amount, item.name),
+                    i,
+                    Some(f!(
+                        "{} • {} de ouro",
+                        listing.kind.name(),
+                        listing.current_price()
+                    )),
+                    Some(ReactionType::Unicode {
+                        name: item.emoji.into(),
+                    }),
+                    false,
+                );
+            }
+
+            components.push(
+                ActionRowBuilder::new()
+                    .add_select_menu(listing_menu)
+                    .build(),
+            );
+        }
+
+        components.push(
+            ActionRowBuilder::new()
+                .add_button(
+                    ButtonBuilder::new()
+                        .set_custom_id(PREVIOUS_BUTTON_ID)
+                        .set_emoji(ReactionType::Unicode {
+                            name: "◀️".into()
+                        })
+                        .set_disabled(self.page == 0),
+                )
+                .add_button(
+                    ButtonBuilder::new()
+                        .set_custom_id(NEXT_BUTTON_ID)
+                        .set_emoji(ReactionType::Unicode {
+                            name: "▶️".into()
+                        })
+                        .set_disabled(self.page + 1 >= self.pages()),
+                )
+                .build(),
+        );
+
+        components
+    }
+}
+
+pub struct MarketCommand;
+
+#[async_trait]
+impl Command for MarketCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "mercado",
+            "Compre itens anunciados por outros jogadores ou dê lances em leilões",
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "lance",
+                "Valor do lance caso você escolha um leilão, o mínimo é usado se não for informado",
+                CommandOptionType::Integer,
+            )
+            .set_required(false),
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+        let bid = ctx.options().get_integer("lance");
+        let Ok(bid) = bid.map(i32::try_from).transpose() else {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "o lance é grande demais!").error_response(),
+                )
+                .await;
+        };
+
+        let mut state = MarketState {
+            kind: 0,
+            category: 0,
+            page: 0,
+            listings: Vec::new(),
+            total: 0,
+        };
+        state.fetch(&ctx).await?;
+
+        let message = ctx.send(state.response(true)).await?;
+
+        let standby = ctx.standby.clone();
+        let mut stream = standby.create_component_stream(
+            message.id,
+            Duration::from_secs(300),
+            move |event: &Interaction| event.author_id() == Some(author_id),
+        );
+
+        let mut selected = None;
+        while let Some(Ok(component)) = stream.next().await {
+            let Some(InteractionData::MessageComponent(data)) = &component.data else {
+                break;
+            };
+
+            let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+            let value = data.values.first().and_then(|v| v.parse::<usize>().ok());
+
+            match data.custom_id.as_str() {
+                KIND_SELECT_ID => {
+                    state.kind = value.filter(|&v| v < KIND_FILTERS.len()).unwrap_or(0);
+                    state.page = 0;
+                }
+                CATEGORY_SELECT_ID => {
+                    state.category = value.filter(|&v| v < CATEGORY_FILTERS.len()).unwrap_or(0);
+                    state.page = 0;
+                }
+                PREVIOUS_BUTTON_ID => state.page = state.page.saturating_sub(1),
+                NEXT_BUTTON_ID => state.page += 1,
+                LISTING_SELECT_ID => {
+                    selected = value.and_then(|i| state.listings.get(i)).cloned();
+                    if selected.is_some() {
+                        ctx.update_interaction(state.response(false)).await.ok();
+                        break;
+                    }
+                }
+                _ => {}
+            }
+
+            state.fetch(&ctx).await?;
+            ctx.update_interaction(state.response(true)).await.ok();
+        }
+
+        let Some(listing) = selected else {
+            return Ok(());
+        };
+
+        let item = get_item_by_type(listing.item).ok_or("Invalid item")?;
+        let author_key = author.id.to_string();
+        let is_seller = listing.seller_id == author_key;
+
+        let amount = match listing.kind {
+            ListingKind::FixedPrice => listing.price,
+            ListingKind::Auction => bid.unwrap_or_else(|| listing.minimum_bid()),
+        };
+
+        let error = if is_seller && listing.highest_bid.is_some() {
+            Some("seu leilão já recebeu lances e não pode mais ser cancelado!".to_string())
+        } else if !is_seller && amount < listing.minimum_bid() {
+            Some(f!(
+                "o lance mínimo para esse leilão é de **{}** {}!",
+                listing.minimum_bid(),
+                Emoji::Gold
+            ))
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            ctx.send_in_channel(Response::new_user_reply(author, error).error_response())
+                .await?;
+            return Ok(());
+        }
+
+        let question = match listing.kind {
+            _ if is_seller => f!(
+                "você quer cancelar seu anúncio de **{}x** {} **{}**? Os itens voltarão para o seu inventário.",
+                listing.amount,
+                item.emoji,
+                item.name
+            ),
+            ListingKind::FixedPrice => f!(
+                "você quer comprar **{}x** {} **{}** por **{}** {}?",
+                listing.amount,
+                item.emoji,
+                item.name,
+                amount,
+                Emoji::Gold
+            ),
+            ListingKind::Auction => f!(
+                "você quer dar um lance de **{}** {} por **{}x** {} **{}**? O ouro fica guardado até o leilão terminar, e é devolvido se alguém der um lance maior.",
+                amount,
+                Emoji::Gold,
+                listing.amount,
+                item.emoji,
+                item.name
+            ),
+        };
+
+        let confirmation = ctx
+            .create_confirmation(
+                author.clone(),
+                Response::new_user_reply(author.clone(), question).set_emoji_prefix("🏪"),
+            )
+            .await;
+
+        if !confirmation {
+            return Ok(());
+        }
+
+        let (success, message) = if is_seller {
+            (
+                ctx.db().cancel_listing(listing.id, &author_key).await?,
+                "você cancelou seu anúncio e recebeu seus itens de volta!".to_string(),
+            )
+        } else if listing.kind == ListingKind::FixedPrice {
+            (
+                ctx.db().buy_listing(listing.id, &author_key).await?,
+                f!(
+                    "você comprou **{}x** {} **{}** por **{}** {}!",
+                    listing.amount,
+                    item.emoji,
+                    item.name,
+                    amount,
+                    Emoji::Gold
+                ),
+            )
+        } else {
+            (
+                ctx.db().place_bid(listing.id, &author_key, amount).await?,
+                f!(
+                    "você deu um lance de **{}** {} por **{}x** {} **{}**!",
+                    amount,
+                    Emoji::Gold,
+                    listing.amount,
+                    item.emoji,
+                    item.name
+                ),
+            )
+        };
+
+        let response = if success {
+            Response::new_user_reply(author, message).success_response()
+        } else {
+            Response::new_user_reply(
+                author,
+                "não foi possível concluir! O anúncio pode já ter terminado, recebido um lance maior, ou você não tem ouro ou espaço suficiente.",
+            )
+            .error_response()
+        };
+
+        ctx.send_in_channel(response).await?;
+
+        Ok(())
+    }
+}
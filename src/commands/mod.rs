@@ -5,13 +5,23 @@ use lazy_static::lazy_static;
 use prelude::*;
 
 mod adventure;
+mod attributes;
 mod battle;
 mod bestiary;
+mod craft;
+mod equip;
+mod history;
+mod inventory;
+mod market;
 mod owner;
 mod ping;
 mod profile;
 mod rest;
+mod sell;
+mod shop;
 mod start;
+mod talents;
+mod trade;
 
 lazy_static! {
     pub static ref COMMANDS: HashMap<&'static str, Box<dyn Command + Send + Sync>> = {
@@ -23,6 +33,16 @@ lazy_static! {
         map.insert("aventura", Box::new(adventure::AdventureCommand));
         map.insert("descansar", Box::new(rest::RestCommand));
         map.insert("bestiário", Box::new(bestiary::BestiaryCommand));
+        map.insert("historico", Box::new(history::HistoryCommand));
+        map.insert("inventario", Box::new(inventory::InventoryCommand));
+        map.insert("equipar", Box::new(equip::EquipCommand));
+        map.insert("loja", Box::new(shop::ShopCommand));
+        map.insert("atributos", Box::new(attributes::AttributesCommand));
+        map.insert("talentos", Box::new(talents::TalentsCommand));
+        map.insert("criar", Box::new(craft::CraftCommand));
+        map.insert("trocar", Box::new(trade::TradeCommand));
+        map.insert("vender", Box::new(sell::SellCommand));
+        map.insert("mercado", Box::new(market::MarketCommand));
         map.insert("owner", Box::new(owner::OwnerCommand));
 
         map
@@ -9,6 +9,15 @@ use super::prelude::*;
 
 pub struct ProfileCommand;
 
+// Shows the base value followed by the bonus given by equipment and talents, when there is one
+fn display_with_bonus(base: impl std::fmt::Display, bonus: i32) -> String {
+    if bonus == 0 {
+        f!("{}", base)
+    } else {
+        f!("{} (`{:+}`)", base, bonus)
+    }
+}
+
 #[async_trait]
 impl Command for ProfileCommand {
     fn command_config(&self) -> CommandConfig {
@@ -28,6 +37,31 @@ impl Command for ProfileCommand {
             .ok_or("User data not found")?;
 
         let class = data::classes::get_class_by_type(user_data.class).ok_or("Invalid class")?;
+        let bonus = user_data.bonus_stats();
+
+        let passives = std::iter::once(class.passive.to_string())
+            .chain(user_data.passives().iter().map(|p| f!("🌳 {}", p)))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let regeneration = match user_data.full_regeneration_at() {
+            Some(full_at) => f!("Vida e mana cheias <t:{}:R>", full_at / 1000),
+            None => "Vida e mana cheias!".into(),
+        };
+
+        let equipment = data::items::ALL_EQUIPMENT_SLOTS
+            .iter()
+            .map(|&slot| {
+                let item = user_data
+                    .equipment
+                    .get(slot)
+                    .and_then(data::items::get_item_by_type)
+                    .map_or("Nada".into(), |i| f!("{} {}", i.emoji, i.name));
+
+                f!("**{}**: {}", slot.name(), item)
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
 
         let embed = EmbedBuilder::new()
             .set_color(Color::BLUE)
@@ -38,7 +72,12 @@ impl Command for ProfileCommand {
             .set_thumbnail(author.avatar_url())
             .add_field(EmbedField {
                 name: f!("{} Classe", class.emoji),
-                value: f!("**{}**", class.name),
+                value: match user_data.get_subclass() {
+                    Some(subclass) => {
+                        f!("**{}** ({} {})", class.name, subclass.emoji, subclass.name)
+                    }
+                    None => f!("**{}**", class.name),
+                },
                 inline: true,
             })
             .add_field(EmbedField {
@@ -59,38 +98,64 @@ impl Command for ProfileCommand {
             .add_field(EmbedField {
                 name: "🔹 Experiência".into(),
                 value: f!(
-                    "XP: **{}**/{}\nNível: **{}**",
+                    "XP: **{}**/{}\nNível: **{}**{}",
                     user_data.xp,
                     math::calculate_xp_required_for_level_up(user_data.level),
-                    user_data.level
+                    user_data.level,
+                    if user_data.attribute_points > 0 {
+                        f!("\nPontos: **{}**", user_data.attribute_points)
+                    } else {
+                        String::new()
+                    }
                 ),
                 inline: true,
             })
             .add_field(EmbedField {
                 name: "❤️ Vida".into(),
-                value: f!("**{}**/{}", user_data.health.value, user_data.health.max),
+                value: display_with_bonus(
+                    f!("**{}**/{}", user_data.health.value, user_data.health.max),
+                    bonus.health,
+                ),
                 inline: true,
             })
             .add_field(EmbedField {
                 name: "🌀 Mana".into(),
-                value: f!("**{}**/{}", user_data.mana.value, user_data.mana.max),
+                value: display_with_bonus(
+                    f!("**{}**/{}", user_data.mana.value, user_data.mana.max),
+                    bonus.mana,
+                ),
+                inline: true,
+            })
+            .add_field(EmbedField {
+                name: "⏳ Recuperação".into(),
+                value: regeneration,
                 inline: true,
             })
             .add_field(EmbedField {
                 name: "💪 Força".into(),
-                value: f!("{}", user_data.strength),
+                value: display_with_bonus(user_data.strength, bonus.strength),
                 inline: true,
             })
             .add_field(EmbedField {
                 name: "🧠 Inteligência".into(),
-                value: f!("{}", user_data.intelligence),
+                value: display_with_bonus(user_data.intelligence, bonus.intelligence),
                 inline: true,
             })
             .add_field(EmbedField {
                 name: "⚡ Agilidade".into(),
-                value: f!("{}", user_data.agility),
+                value: display_with_bonus(user_data.agility, bonus.agility),
                 inline: true,
             })
+            .add_field(EmbedField {
+                name: "✨ Passivas".into(),
+                value: passives,
+                inline: false,
+            })
+            .add_field(EmbedField {
+                name: "🎒 Equipamento".into(),
+                value: equipment,
+                inline: false,
+            })
             .set_current_timestamp();
 
         ctx.reply(Response::from_embeds(vec![embed])).await?;
@@ -1,5 +1,8 @@
 use super::prelude::*;
 
+// Percentage of the max health and mana restored by resting, the rest regenerates over time
+const REST_RESTORE_PERCENTAGE: f32 = 0.4;
+
 pub struct RestCommand;
 
 #[async_trait]
@@ -20,11 +23,12 @@ impl Command for RestCommand {
         let author_id = ctx.author_id()?;
 
         let author = ctx.author().await?;
-        let mut author_data = ctx
+        let original = ctx
             .db()
             .get_user_data(&author.id.to_string())
             .await?
             .ok_or("Invalid data")?;
+        let mut author_data = original.clone();
 
         if author_data.health.value as f32 > (author_data.health.max as f32 * 0.95) {
             return ctx
@@ -36,19 +40,47 @@ impl Command for RestCommand {
         }
 
         let cooldown_check = ctx
-            .check_user_cooldown(author_id, CooldownType::Rest, Duration::minutes(20))
+            .check_user_cooldown(author_id, CooldownType::Rest, Duration::minutes(5))
             .await?;
         if cooldown_check == CommandFlow::ShouldStop {
             return Ok(());
         }
 
-        author_data.restore_health();
-        author_data.restore_mana();
+        let health = (author_data.health.max as f32 * REST_RESTORE_PERCENTAGE) as i32;
+        let mana = (author_data.mana.max as f32 * REST_RESTORE_PERCENTAGE) as i32;
+        author_data.add_health(health);
+        author_data.add_mana(mana);
 
-        ctx.db().save_user_data(author_data).await?;
+        if !ctx
+            .db()
+            .save_user_data_changes(&original, author_data)
+            .await?
+        {
+            return ctx
+                .reply(
+                    Response::new_user_reply(
+                        author,
+                        "não foi possível descansar, tente novamente!",
+                    )
+                    .error_response(),
+                )
+                .await;
+        }
 
-        ctx.reply(Response::new_user_reply(author, "você descansou!").set_emoji_prefix("⚡"))
-            .await?;
+        ctx.reply(
+            Response::new_user_reply(
+                author,
+                f!(
+                    "você descansou e recuperou até **{}** {} e **{}** {}! Vida e mana também se recuperam com o tempo.",
+                    health,
+                    Emoji::Health,
+                    mana,
+                    Emoji::Mana
+                ),
+            )
+            .set_emoji_prefix("⚡"),
+        )
+        .await?;
 
         Ok(())
     }
@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use data::items::get_item_by_type;
+use database::market::{Listing, ListingKind};
+use twilight_model::{application::interaction::InteractionData, channel::message::ReactionType};
+
+use super::prelude::*;
+
+const MAX_ACTIVE_LISTINGS: u64 = 10;
+const FIXED_PRICE_DAYS: i64 = 3;
+const MAX_AUCTION_HOURS: i64 = 72;
+
+const ITEM_SELECT_ID: &str = "item";
+
+pub struct SellCommand;
+
+#[async_trait]
+impl Command for SellCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "vender",
+            "Anuncie um item no mercado para outros jogadores",
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "preço",
+                "Preço do anúncio, ou o lance inicial se for um leilão",
+                CommandOptionType::Integer,
+            )
+            .set_required(true),
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "quantidade",
+                "Quantos itens você quer vender",
+                CommandOptionType::Integer,
+            )
+            .set_required(false),
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "leilão",
+                "Duração do leilão em horas. Sem ela, o item é vendido por preço fixo",
+                CommandOptionType::Integer,
+            )
+            .set_required(false),
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+
+        let (Ok(price), Ok(quantity)) = (
+            i32::try_from(ctx.options().get_integer("preço").unwrap_or(0)),
+            i32::try_from(ctx.options().get_integer("quantidade").unwrap_or(1)),
+        ) else {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "o preço ou a quantidade é grande demais!")
+                        .error_response(),
+                )
+                .await;
+        };
+        let auction_hours = ctx.options().get_integer("leilão");
+
+        let error = if price < 1 || quantity < 1 {
+            Some("o preço e a quantidade precisam ser maiores que zero!".to_string())
+        } else if auction_hours.is_some_and(|h| !(1..=MAX_AUCTION_HOURS).contains(&h)) {
+            Some(f!(
+                "um leilão precisa durar entre 1 e {} horas!",
+                MAX_AUCTION_HOURS
+            ))
+        } else if ctx.db().count_user_listings(&author.id.to_string()).await? >= MAX_ACTIVE_LISTINGS
+        {
+            Some(f!(
+                "você já tem **{}** anúncios ativos no mercado!",
+                MAX_ACTIVE_LISTINGS
+            ))
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            return ctx
+                .reply(Response::new_user_reply(author, error).error_response())
+                .await;
+        }
+
+        let (kind, duration) = match auction_hours {
+            Some(hours) => (ListingKind::Auction, chrono::Duration::hours(hours)),
+            None => (
+                ListingKind::FixedPrice,
+                chrono::Duration::days(FIXED_PRICE_DAYS),
+            ),
+        };
+
+        let author_data = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        let items = author_data
+            .inventory
+            .iter()
+            .filter(|(_, &amount)| amount >= quantity)
+            .filter_map(|(&item, &amount)| get_item_by_type(item).map(|i| (i, amount)))
+            .collect::<Vec<_>>();
+
+        if items.is_empty() {
+            return ctx
+                .reply(
+                    Response::new_user_reply(
+                        author,
+                        f!("você não tem nenhum item em quantidade suficiente para vender **{}x**!", quantity),
+                    )
+                    .error_response(),
+                )
+                .await;
+        }
+
+        let mut select_menu =
+            SelectMenuBuilder::new(ITEM_SELECT_ID).set_placeholder("Escolha um item para vender");
+
+        for (i, (item, amount)) in items.iter().enumerate().take(25) {
+            select_menu = select_menu.add_option(
+                f!("{} ({}x)", item.name, amount),
+                i,
+                None,
+                Some(ReactionType::Unicode {
+                    name: item.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        let response = Response {
+            components: Some(vec![ActionRowBuilder::new()
+                .add_select_menu(select_menu)
+                .build()]),
+            ..Response::new_user_reply(author.clone(), "qual item você quer anunciar?")
+                .set_emoji_prefix("🏷️")
+        };
+
+        let message = ctx.send(response.clone()).await?;
+
+        let standby = ctx.standby.clone();
+        let Ok(Some(component)) = standby.wait_for_component_with_duration(message.id, Duration::from_secs(60), move |event: &Interaction| {
+            event.author_id() == Some(author_id)
+        }).await else {
+            return Ok(());
+        };
+
+        let Some(InteractionData::MessageComponent(data)) = &component.data else {
+            return Ok(());
+        };
+
+        let (item, _) = data
+            .values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|i| items.get(i))
+            .ok_or("Invalid item")?;
+
+        let mut ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+        ctx.update_interaction(response.remove_all_components())
+            .await
+            .ok();
+
+        let description = match kind {
+            ListingKind::FixedPrice => f!(
+                "por **{}** {} durante {} dias",
+                price,
+                Emoji::Gold,
+                FIXED_PRICE_DAYS
+            ),
+            ListingKind::Auction => f!(
+                "em um leilão de {} horas com lance inicial de **{}** {}",
+                duration.num_hours(),
+                price,
+                Emoji::Gold
+            ),
+        };
+
+        let confirmation = ctx
+            .create_confirmation(
+                author.clone(),
+                Response::new_user_reply(
+                    author.clone(),
+                    f!(
+                        "você quer anunciar **{}x** {} **{}** {}? Os itens ficarão guardados no mercado até o anúncio terminar.",
+                        quantity,
+                        item.emoji,
+                        item.name,
+                        description
+                    ),
+                )
+                .set_emoji_prefix("🏷️"),
+            )
+            .await;
+
+        if !confirmation {
+            return Ok(());
+        }
+
+        let listing = Listing::new(
+            author.id.to_string(),
+            item.item_type,
+            quantity,
+            kind,
+            price,
+            duration,
+        );
+
+        if !ctx.db().create_listing(listing).await? {
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!("você não tem mais **{}x** {}!", quantity, item.name),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.send_in_channel(
+            Response::new_user_reply(
+                author,
+                f!(
+                    "você anunciou **{}x** {} **{}** {}! Veja o mercado com **/mercado**.",
+                    quantity,
+                    item.emoji,
+                    item.name,
+                    description
+                ),
+            )
+            .success_response(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
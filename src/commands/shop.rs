@@ -0,0 +1,254 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use data::{regions::RegionType, shop};
+use twilight_model::{application::interaction::InteractionData, channel::message::ReactionType};
+
+use super::prelude::*;
+
+const OFFER_SELECT_ID: &str = "offer";
+const SECONDS_IN_A_DAY: i64 = 60 * 60 * 24;
+
+pub struct ShopCommand;
+
+#[async_trait]
+impl Command for ShopCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "loja",
+            "Compre itens na loja da cidade em que você está",
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "quantidade",
+                "Quantos itens você quer comprar",
+                CommandOptionType::Integer,
+            )
+            .set_required(false),
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+        let author_data = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        let quantity = ctx.options().get_integer("quantidade").unwrap_or(1);
+        let Ok(quantity) = i32::try_from(quantity) else {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "a quantidade é grande demais!")
+                        .error_response(),
+                )
+                .await;
+        };
+        if quantity < 1 {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "a quantidade precisa ser maior que zero!")
+                        .error_response(),
+                )
+                .await;
+        }
+
+        let city = author_data.journey.current_region.clone();
+        if city.region_type != RegionType::City {
+            return ctx
+                .reply(
+                    Response::new_user_reply(
+                        author,
+                        "não há nenhuma loja por aqui! Você precisa estar em uma cidade para comprar itens.",
+                    )
+                    .error_response(),
+                )
+                .await;
+        }
+
+        let day = Utc::now().timestamp() / SECONDS_IN_A_DAY;
+        let offers = shop::generate_city_stock(&city.name, day);
+
+        let mut select_menu =
+            SelectMenuBuilder::new(OFFER_SELECT_ID).set_placeholder("Escolha um item para comprar");
+        let mut description = Vec::new();
+
+        for (i, offer) in offers.iter().enumerate() {
+            description.push(f!(
+                "{} **{}** — **{}** {} ({} {})",
+                offer.item.emoji,
+                offer.item.name,
+                offer.price,
+                Emoji::Gold,
+                offer.item.rarity.emoji(),
+                offer.item.rarity.name()
+            ));
+
+            select_menu = select_menu.add_option(
+                offer.item.name,
+                i,
+                Some(f!("{} de ouro cada", offer.price)),
+                Some(ReactionType::Unicode {
+                    name: offer.item.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        let embed = EmbedBuilder::new()
+            .set_author(EmbedAuthor {
+                name: f!("Loja de {}", city.name),
+                icon_url: Some(author.avatar_url()),
+            })
+            .set_color(Color::ORANGE)
+            .set_description(f!(
+                "{}\n\nVocê tem **{}** {}. O estoque muda <t:{}:R>.",
+                description.join("\n"),
+                author_data.gold,
+                Emoji::Gold,
+                (day + 1) * SECONDS_IN_A_DAY
+            ))
+            .set_current_timestamp();
+
+        let response = Response {
+            embeds: Some(vec![embed]),
+            components: Some(vec![ActionRowBuilder::new()
+                .add_select_menu(select_menu)
+                .build()]),
+            ..Default::default()
+        };
+
+        let message = ctx.send(response.clone()).await?;
+
+        let standby = ctx.standby.clone();
+        let Ok(Some(component)) = standby.wait_for_component_with_duration(message.id, Duration::from_secs(60), move |event: &Interaction| {
+            event.author_id() == Some(author_id)
+        }).await else {
+            return Ok(());
+        };
+
+        let Some(InteractionData::MessageComponent(data)) = &component.data else {
+            return Ok(());
+        };
+
+        let offer = data
+            .values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|i| offers.get(i))
+            .ok_or("Invalid offer")?;
+
+        let mut ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+        ctx.update_interaction(response.remove_all_components())
+            .await
+            .ok();
+
+        let Some(total) = offer.price.checked_mul(quantity) else {
+            ctx.send_in_channel(
+                Response::new_user_reply(author, "a quantidade é grande demais!").error_response(),
+            )
+            .await?;
+            return Ok(());
+        };
+
+        let confirmation = ctx
+            .create_confirmation(
+                author.clone(),
+                Response::new_user_reply(
+                    author.clone(),
+                    f!(
+                        "você quer comprar **{}x** {} **{}** por **{}** {}?",
+                        quantity,
+                        offer.item.emoji,
+                        offer.item.name,
+                        total,
+                        Emoji::Gold
+                    ),
+                )
+                .set_emoji_prefix("🛒"),
+            )
+            .await;
+
+        if !confirmation {
+            return Ok(());
+        }
+
+        // The data may have changed while the player was choosing
+        let original = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+        let mut author_data = original.clone();
+
+        let space = offer.item.max_stack - author_data.item_amount(offer.item.item_type);
+
+        let error = if author_data.gold < total {
+            Some(f!(
+                "você não tem ouro suficiente! Faltam **{}** {}.",
+                total - author_data.gold,
+                Emoji::Gold
+            ))
+        } else if space < quantity {
+            Some(f!(
+                "você só consegue carregar mais **{}** {}!",
+                space.max(0),
+                offer.item.name
+            ))
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            ctx.send_in_channel(Response::new_user_reply(author, error).error_response())
+                .await?;
+            return Ok(());
+        }
+
+        author_data.remove_gold(total);
+        author_data.add_item(offer.item.item_type, quantity);
+
+        // The gold may have been spent elsewhere before this is saved
+        if !ctx
+            .db()
+            .save_user_data_changes(&original, author_data)
+            .await?
+        {
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    f!("você não tem mais **{}** {}!", total, Emoji::Gold),
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.send_in_channel(
+            Response::new_user_reply(
+                author,
+                f!(
+                    "você comprou **{}x** {} **{}** por **{}** {}!",
+                    quantity,
+                    offer.item.emoji,
+                    offer.item.name,
+                    total,
+                    Emoji::Gold
+                ),
+            )
+            .success_response(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -42,7 +42,7 @@ impl Command for StartCommand {
                     .iter()
                     .map(|c| EmbedField {
                         name: f!("{}, {}", c.emoji, c.name),
-                        value: c.description.into(),
+                        value: f!("{}\n{}", c.description, c.passive),
                         inline: false,
                     })
                     .collect(),
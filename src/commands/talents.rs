@@ -0,0 +1,327 @@
+use std::time::Duration;
+
+use data::{
+    classes::get_class_by_type,
+    skill_trees::{
+        get_skill_tree, get_subclass_by_type, get_subclasses_of_class, SkillNode, SUBCLASS_LEVEL,
+    },
+};
+use database::user_model::UserData;
+use tokio_stream::StreamExt;
+use twilight_model::{
+    application::interaction::InteractionData,
+    channel::message::{Component, ReactionType},
+    user::User,
+};
+
+use super::prelude::*;
+
+const SUBCLASS_SELECT_ID: &str = "subclass";
+const NODE_SELECT_ID: &str = "node";
+
+fn node_status(data: &UserData, node: &SkillNode) -> &'static str {
+    if data.has_skill_node(node.node_type) {
+        "✅"
+    } else if data.can_unlock_skill_node(node) {
+        "🔓"
+    } else {
+        "🔒"
+    }
+}
+
+// Base nodes plus the ones of the chosen subclass, or of every subclass while none was chosen
+fn visible_nodes(data: &UserData) -> Vec<SkillNode> {
+    get_skill_tree(data.class)
+        .into_iter()
+        .filter(|n| data.subclass.is_none() || n.subclass.is_none() || n.subclass == data.subclass)
+        .collect()
+}
+
+fn get_talents_response(user: &User, data: &UserData, active: bool) -> Response {
+    let subclass = match data.get_subclass() {
+        Some(subclass) => f!("{} **{}**", subclass.emoji, subclass.name),
+        None if data.level >= SUBCLASS_LEVEL => "Escolha sua subclasse abaixo!".into(),
+        None => f!("Disponível no nível **{}**", SUBCLASS_LEVEL),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .set_author(EmbedAuthor {
+            name: f!("Talentos de {}", user.name),
+            icon_url: Some(user.avatar_url()),
+        })
+        .set_color(Color::ORANGE)
+        .set_description(f!(
+            "Subclasse: {}\nPontos de talento: **{}**\n\nVocê ganha um ponto a cada nível.",
+            subclass,
+            data.skill_points()
+        ))
+        .set_current_timestamp();
+
+    for node in visible_nodes(data) {
+        let subclass = node
+            .subclass
+            .and_then(get_subclass_by_type)
+            .map_or(String::new(), |s| f!(" • {}", s.name));
+
+        embed = embed.add_field(EmbedField {
+            name: f!("{} {} {}", node_status(data, &node), node.emoji, node.name),
+            value: f!(
+                "{}\nCusto: **{}** • Nível **{}**{}",
+                node.display_effects(),
+                node.cost,
+                node.required_level,
+                subclass
+            ),
+            inline: false,
+        });
+    }
+
+    let response = Response::from_embeds(vec![embed]);
+    if !active {
+        return response.remove_all_components();
+    }
+
+    let components = get_talents_components(data);
+    if components.is_empty() {
+        return response;
+    }
+
+    Response {
+        components: Some(components),
+        ..response
+    }
+}
+
+fn get_talents_components(data: &UserData) -> Vec<Component> {
+    let mut components = Vec::new();
+
+    let subclasses = get_subclasses_of_class(data.class)
+        .into_iter()
+        .filter(|s| data.can_choose_subclass(s))
+        .collect::<Vec<_>>();
+
+    if !subclasses.is_empty() {
+        let mut select_menu = SelectMenuBuilder::new(SUBCLASS_SELECT_ID)
+            .set_placeholder("Escolha sua subclasse (permanente)");
+
+        for (i, subclass) in subclasses.iter().enumerate() {
+            select_menu = select_menu.add_option(
+                subclass.name,
+                i,
+                None,
+                Some(ReactionType::Unicode {
+                    name: subclass.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        components.push(ActionRowBuilder::new().add_select_menu(select_menu).build());
+    }
+
+    let nodes = visible_nodes(data)
+        .into_iter()
+        .filter(|n| data.can_unlock_skill_node(n))
+        .collect::<Vec<_>>();
+
+    if !nodes.is_empty() {
+        let mut select_menu =
+            SelectMenuBuilder::new(NODE_SELECT_ID).set_placeholder("Desbloqueie um talento");
+
+        for node in nodes {
+            select_menu = select_menu.add_option(
+                f!("{} ({} pontos)", node.name, node.cost),
+                node.node_type as usize,
+                None,
+                Some(ReactionType::Unicode {
+                    name: node.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        components.push(ActionRowBuilder::new().add_select_menu(select_menu).build());
+    }
+
+    components
+}
+
+pub struct TalentsCommand;
+
+#[async_trait]
+impl Command for TalentsCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "talentos",
+            "Veja a árvore de talentos da sua classe e escolha sua subclasse",
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let author_id = author.id;
+        let author_key = author.id.to_string();
+
+        let author_data = ctx
+            .db()
+            .get_user_data(&author_key)
+            .await?
+            .ok_or("Invalid data")?;
+
+        let message = ctx
+            .send(get_talents_response(&author, &author_data, true))
+            .await?;
+
+        let standby = ctx.standby.clone();
+        let mut stream = standby.create_component_stream(
+            message.id,
+            Duration::from_secs(120),
+            move |event: &Interaction| event.author_id() == Some(author_id),
+        );
+
+        let mut chosen_subclass = None;
+        while let Some(Ok(component)) = stream.next().await {
+            let Some(InteractionData::MessageComponent(data)) = &component.data else {
+                break;
+            };
+
+            let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+            let value = data.values.first().and_then(|v| v.parse::<usize>().ok());
+
+            // Fetched again on every choice, so nothing done elsewhere in the meantime is overwritten
+            let original = ctx
+                .db()
+                .get_user_data(&author_key)
+                .await?
+                .ok_or("Invalid data")?;
+            let mut author_data = original.clone();
+
+            match data.custom_id.as_str() {
+                SUBCLASS_SELECT_ID => {
+                    let subclasses = get_subclasses_of_class(author_data.class)
+                        .into_iter()
+                        .filter(|s| author_data.can_choose_subclass(s))
+                        .collect::<Vec<_>>();
+
+                    chosen_subclass = value.and_then(|i| subclasses.get(i)).copied();
+                    if chosen_subclass.is_some() {
+                        ctx.update_interaction(get_talents_response(&author, &author_data, false))
+                            .await
+                            .ok();
+                        break;
+                    }
+                }
+                NODE_SELECT_ID => {
+                    let node = visible_nodes(&author_data)
+                        .into_iter()
+                        .find(|n| Some(n.node_type as usize) == value);
+
+                    if let Some(node) = node {
+                        if author_data.unlock_skill_node(&node)
+                            && !ctx
+                                .db()
+                                .save_user_data_changes(&original, author_data.clone())
+                                .await?
+                        {
+                            ctx.send_in_channel(
+                                Response::new_user_reply(
+                                    author,
+                                    "não foi possível salvar seus talentos, tente novamente!",
+                                )
+                                .error_response(),
+                            )
+                            .await?;
+
+                            return Ok(());
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            ctx.update_interaction(get_talents_response(&author, &author_data, true))
+                .await
+                .ok();
+        }
+
+        let Some(subclass) = chosen_subclass else {
+            return Ok(());
+        };
+
+        let class = get_class_by_type(subclass.class).ok_or("Invalid class")?;
+        let confirmation = ctx
+            .create_confirmation(
+                author.clone(),
+                Response::new_user_reply(
+                    author.clone(),
+                    f!(
+                        "você quer evoluir de **{}** para {} **{}**? Essa escolha é permanente!\n*{}*",
+                        class.name,
+                        subclass.emoji,
+                        subclass.name,
+                        subclass.description
+                    ),
+                )
+                .set_emoji_prefix(class.emoji),
+            )
+            .await;
+
+        if !confirmation {
+            return Ok(());
+        }
+
+        let original = ctx
+            .db()
+            .get_user_data(&author_key)
+            .await?
+            .ok_or("Invalid data")?;
+        let mut author_data = original.clone();
+
+        if !author_data.choose_subclass(&subclass) {
+            ctx.send_in_channel(
+                Response::new_user_reply(author, "você não pode mais escolher essa subclasse!")
+                    .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        if !ctx
+            .db()
+            .save_user_data_changes(&original, author_data)
+            .await?
+        {
+            ctx.send_in_channel(
+                Response::new_user_reply(
+                    author,
+                    "não foi possível salvar sua subclasse, tente novamente!",
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.send_in_channel(
+            Response::new_user_reply(
+                author,
+                f!(
+                    "você agora é um {} **{}**! Desbloqueie os talentos da sua subclasse com **/talentos**.",
+                    subclass.emoji,
+                    subclass.name
+                ),
+            )
+            .success_response(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,399 @@
+use std::time::Duration;
+
+use data::items::{Item, ALL_ITEMS};
+use database::{trade::TradeOffer, user_model::UserData};
+use tokio_stream::StreamExt;
+use twilight_model::{
+    application::interaction::InteractionData,
+    channel::message::{component::ButtonStyle, Component, ReactionType},
+    user::User,
+};
+
+use super::prelude::*;
+
+const GOLD_AMOUNTS: [i32; 3] = [10, 100, 1000];
+
+const ITEM_SELECT_ID: &str = "items";
+const GOLD_BUTTON_ID: &str = "gold";
+const CLEAR_BUTTON_ID: &str = "clear";
+const CONFIRM_BUTTON_ID: &str = "confirm";
+const CANCEL_BUTTON_ID: &str = "cancel";
+
+struct TradeSide {
+    user: User,
+    data: UserData,
+    offer: TradeOffer,
+    confirmed: bool,
+}
+
+impl TradeSide {
+    fn new(user: User, data: UserData) -> Self {
+        Self {
+            user,
+            data,
+            offer: TradeOffer::default(),
+            confirmed: false,
+        }
+    }
+
+    // Items and amounts that can still be put in the offer, indexed by their position in ALL_ITEMS
+    fn available_items(&self) -> Vec<(usize, Item, i32)> {
+        ALL_ITEMS
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let available =
+                    self.data.item_amount(item.item_type) - self.offer.item_amount(item.item_type);
+                (i, *item, available)
+            })
+            .filter(|(_, _, available)| *available > 0)
+            .collect()
+    }
+
+    fn display_offer(&self) -> String {
+        let mut lines = Vec::new();
+
+        if self.offer.gold > 0 {
+            lines.push(f!("{} **{}** de ouro", Emoji::Gold, self.offer.gold));
+        }
+
+        for (&item_type, &amount) in &self.offer.items {
+            if let Some(item) = ALL_ITEMS.iter().find(|i| i.item_type == item_type) {
+                lines.push(f!("{} **{}x** {}", item.emoji, amount, item.name));
+            }
+        }
+
+        if lines.is_empty() {
+            return "Nada".into();
+        }
+
+        lines.join("\n")
+    }
+}
+
+fn get_trade_response(sides: &[TradeSide; 2], active: bool) -> Response {
+    let mut embed = EmbedBuilder::new()
+        .set_author(EmbedAuthor {
+            name: f!("Troca entre {} e {}", sides[0].user.name, sides[1].user.name),
+            icon_url: Some(sides[0].user.avatar_url()),
+        })
+        .set_color(Color::ORANGE)
+        .set_description(
+            "Adicione ouro e itens à sua oferta. A troca só acontece quando os dois confirmarem, e qualquer mudança cancela as confirmações.",
+        )
+        .set_current_timestamp();
+
+    for side in sides {
+        let status = if side.confirmed { "✅" } else { "⏳" };
+
+        embed = embed.add_field(EmbedField {
+            name: f!("{} Oferta de {}", status, side.user.name),
+            value: side.display_offer(),
+            inline: true,
+        });
+    }
+
+    let response = Response::from_embeds(vec![embed]);
+    if !active {
+        return response.remove_all_components();
+    }
+
+    Response {
+        components: Some(get_trade_components(sides)),
+        ..response
+    }
+}
+
+fn get_trade_components(sides: &[TradeSide; 2]) -> Vec<Component> {
+    let mut components = Vec::new();
+
+    // Each player has their own menu, since they can only offer what they own
+    for (index, side) in sides.iter().enumerate() {
+        let items = side.available_items();
+        if items.is_empty() {
+            continue;
+        }
+
+        let mut select_menu = SelectMenuBuilder::new(f!("{}_{}", ITEM_SELECT_ID, index))
+            .set_placeholder(f!("Itens de {} (adiciona 1 por vez)", side.user.name));
+
+        for (i, item, available) in items.into_iter().take(25) {
+            select_menu = select_menu.add_option(
+                f!("{} ({}x)", item.name, available),
+                i,
+                None,
+                Some(ReactionType::Unicode {
+                    name: item.emoji.into(),
+                }),
+                false,
+            );
+        }
+
+        components.push(ActionRowBuilder::new().add_select_menu(select_menu).build());
+    }
+
+    let mut gold_buttons: Vec<ButtonBuilder> = GOLD_AMOUNTS
+        .iter()
+        .map(|amount| {
+            ButtonBuilder::new()
+                .set_custom_id(f!("{}_{}", GOLD_BUTTON_ID, amount))
+                .set_emoji(ReactionType::Unicode {
+                    name: Emoji::Gold.into(),
+                })
+                .set_label(f!("+{}", amount))
+        })
+        .collect();
+
+    gold_buttons.push(
+        ButtonBuilder::new()
+            .set_custom_id(CLEAR_BUTTON_ID)
+            .set_emoji(ReactionType::Unicode {
+                name: "🧹".into()
+            })
+            .set_label("Limpar oferta"),
+    );
+
+    components.push(ActionRowBuilder::new().add_buttons(gold_buttons).build());
+
+    components.push(
+        ActionRowBuilder::new()
+            .add_button(
+                ButtonBuilder::new()
+                    .set_custom_id(CONFIRM_BUTTON_ID)
+                    .set_emoji(ReactionType::Unicode { name: "✅".into() })
+                    .set_label("Confirmar")
+                    .set_style(ButtonStyle::Success)
+                    .set_disabled(sides.iter().all(|s| s.offer.is_empty())),
+            )
+            .add_button(
+                ButtonBuilder::new()
+                    .set_custom_id(CANCEL_BUTTON_ID)
+                    .set_emoji(ReactionType::Unicode { name: "❌".into() })
+                    .set_label("Cancelar")
+                    .set_style(ButtonStyle::Danger),
+            )
+            .build(),
+    );
+
+    components
+}
+
+pub struct TradeCommand;
+
+#[async_trait]
+impl Command for TradeCommand {
+    fn command_config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+
+    fn build_command(&self, application_id: Id<ApplicationMarker>) -> CommandBuilder {
+        CommandBuilder::new(
+            application_id,
+            "trocar",
+            "Troque ouro e itens com outro jogador",
+        )
+        .add_option(
+            CommandOptionBuilder::new(
+                "usuário",
+                "Usuário com quem você quer trocar",
+                CommandOptionType::User,
+            )
+            .set_required(true),
+        )
+    }
+
+    async fn run(&self, mut ctx: CommandContext) -> CommandResult {
+        let author = ctx.author().await?;
+        let user = ctx
+            .options()
+            .get_user("usuário")
+            .await?
+            .ok_or("User expected")?;
+
+        if user.id == author.id {
+            return ctx
+                .reply(
+                    Response::new_user_reply(author, "você não pode trocar com você mesmo!")
+                        .error_response(),
+                )
+                .await;
+        }
+
+        if !ctx.db().is_user_registered(&user.id.to_string()).await {
+            ctx.send(
+                Response::from_string(f!("**{}** não iniciou sua jornada ainda!", user.name))
+                    .error_response(),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let confirmation = ctx
+            .create_confirmation(
+                user.clone(),
+                Response::new_user_reply(
+                    user.clone(),
+                    f!(
+                        "**{}** quer fazer uma troca com você! Você aceita?",
+                        author.name
+                    ),
+                )
+                .set_emoji_prefix("🤝"),
+            )
+            .await;
+
+        if !confirmation {
+            return Ok(());
+        }
+
+        let author_data = ctx
+            .db()
+            .get_user_data(&author.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        let user_data = ctx
+            .db()
+            .get_user_data(&user.id.to_string())
+            .await?
+            .ok_or("Invalid data")?;
+
+        let mut sides = [
+            TradeSide::new(author.clone(), author_data),
+            TradeSide::new(user.clone(), user_data),
+        ];
+
+        let message = ctx.send(get_trade_response(&sides, true)).await?;
+
+        let (author_id, user_id) = (author.id, user.id);
+        let standby = ctx.standby.clone();
+        let mut stream = standby.create_component_stream(
+            message.id,
+            Duration::from_secs(180),
+            move |event: &Interaction| {
+                event.author_id() == Some(author_id) || event.author_id() == Some(user_id)
+            },
+        );
+
+        let mut settled = false;
+        while let Some(Ok(component)) = stream.next().await {
+            let Some(InteractionData::MessageComponent(data)) = &component.data else {
+                continue;
+            };
+
+            let Some(index) = sides
+                .iter()
+                .position(|s| Some(s.user.id) == component.author_id())
+            else {
+                continue;
+            };
+
+            let ctx = CommandContext::from_with_interaction(&ctx, Box::new(component.clone()));
+            let side = &mut sides[index];
+            let mut changed = false;
+
+            match data.custom_id.as_str() {
+                CONFIRM_BUTTON_ID => side.confirmed = true,
+                CANCEL_BUTTON_ID => {
+                    ctx.update_interaction(get_trade_response(&sides, false))
+                        .await
+                        .ok();
+
+                    ctx.send_in_channel(
+                        Response::from_string(f!(
+                            "**{}** cancelou a troca.",
+                            sides[index].user.name
+                        ))
+                        .set_emoji_prefix("🤝"),
+                    )
+                    .await?;
+
+                    return Ok(());
+                }
+                CLEAR_BUTTON_ID => {
+                    side.offer = TradeOffer::default();
+                    changed = true;
+                }
+                custom_id => {
+                    if let Some(amount) = custom_id
+                        .strip_prefix(GOLD_BUTTON_ID)
+                        .and_then(|a| a.trim_start_matches('_').parse::<i32>().ok())
+                    {
+                        let amount = amount.min(side.data.gold - side.offer.gold);
+                        if amount > 0 {
+                            side.offer.gold += amount;
+                            changed = true;
+                        }
+                    } else if custom_id == f!("{}_{}", ITEM_SELECT_ID, index) {
+                        let item = data
+                            .values
+                            .first()
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .and_then(|i| {
+                                side.available_items().into_iter().find(|(j, ..)| *j == i)
+                            });
+
+                        if let Some((_, item, _)) = item {
+                            side.offer.add_item(item.item_type, 1);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            // Any change to the offers requires both players to confirm again
+            if changed {
+                for side in sides.iter_mut() {
+                    side.confirmed = false;
+                }
+            }
+
+            settled = sides.iter().all(|s| s.confirmed);
+
+            ctx.update_interaction(get_trade_response(&sides, !settled))
+                .await
+                .ok();
+
+            if settled {
+                break;
+            }
+        }
+
+        if !settled {
+            return Ok(());
+        }
+
+        let [first, second] = &sides;
+        let success = ctx
+            .db()
+            .execute_trade(
+                (&first.user.id.to_string(), &first.offer),
+                (&second.user.id.to_string(), &second.offer),
+            )
+            .await?;
+
+        if !success {
+            ctx.send_in_channel(
+                Response::from_string(
+                    "A troca falhou! Algum dos jogadores não tem mais o que ofereceu ou não tem espaço para o que iria receber.",
+                )
+                .error_response(),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        ctx.send_in_channel(
+            Response::from_string(f!(
+                "**{}** e **{}** concluíram a troca!",
+                first.user.name,
+                second.user.name
+            ))
+            .set_emoji_prefix("🤝"),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(unused)]
 use twilight_model::channel::message::{
-    component::{ActionRow, Button, ButtonStyle},
+    component::{ActionRow, Button, ButtonStyle, SelectMenu, SelectMenuOption},
     Component, ReactionType,
 };
 
@@ -68,6 +68,58 @@ impl ButtonBuilder {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectMenuBuilder {
+    data: SelectMenu,
+}
+
+impl SelectMenuBuilder {
+    pub fn new(custom_id: impl ToString) -> Self {
+        Self {
+            data: SelectMenu {
+                custom_id: custom_id.to_string(),
+                disabled: false,
+                max_values: None,
+                min_values: None,
+                options: vec![],
+                placeholder: None,
+            },
+        }
+    }
+
+    pub fn set_placeholder(mut self, placeholder: impl ToString) -> Self {
+        self.data.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    pub fn set_disabled(mut self, disabled: bool) -> Self {
+        self.data.disabled = disabled;
+        self
+    }
+
+    pub fn add_option(
+        mut self,
+        label: impl ToString,
+        value: impl ToString,
+        description: Option<String>,
+        emoji: Option<ReactionType>,
+        default: bool,
+    ) -> Self {
+        self.data.options.push(SelectMenuOption {
+            default,
+            description,
+            emoji,
+            label: label.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    pub fn build(self) -> Component {
+        Component::SelectMenu(self.data)
+    }
+}
+
 impl ActionRowBuilder {
     pub fn new() -> Self {
         Self {
@@ -80,6 +132,11 @@ impl ActionRowBuilder {
         self
     }
 
+    pub fn add_select_menu(mut self, select_menu: SelectMenuBuilder) -> Self {
+        self.data.components.push(select_menu.build());
+        self
+    }
+
     pub fn add_buttons(mut self, buttons: Vec<ButtonBuilder>) -> Self {
         let mut buttons: Vec<Component> = buttons.iter().cloned().map(|b| b.build()).collect();
         self.data.components.append(&mut buttons);
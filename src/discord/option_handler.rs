@@ -34,4 +34,18 @@ impl<'a> OptionHandler<'a> {
             _ => None,
         })
     }
+
+    pub fn get_integer(&self, option_name: impl Into<String>) -> Option<i64> {
+        let option_name: String = option_name.into();
+        let Some(InteractionData::ApplicationCommand(data)) = &self.ctx.interaction.data else {
+            return None;
+        };
+
+        let option = data.options.iter().find(|o| o.name == option_name)?;
+
+        match option.value {
+            CommandOptionValue::Integer(value) => Some(value),
+            _ => None,
+        }
+    }
 }
@@ -9,7 +9,7 @@ mod prelude;
 use database::Database;
 use event_handler::EventHandler;
 use futures_util::StreamExt;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use twilight_gateway::{
     cluster::{ClusterBuilder, ShardScheme},
     Event, Intents,
@@ -65,6 +65,22 @@ async fn main() -> Result<(), DynamicError> {
         .await,
     );
 
+    // Auctions and listings that ran out of time are settled in the background
+    let settlement_database = database.clone();
+    util::set_tokio_interval(
+        move || {
+            let database = settlement_database.clone();
+            async move {
+                match database.settle_expired_listings().await {
+                    Ok(0) => {}
+                    Ok(settled) => tracing::info!("Settled {} expired listings", settled),
+                    Err(err) => tracing::error!("Failed to settle expired listings: {}", err),
+                }
+            }
+        },
+        Duration::from_secs(60),
+    );
+
     let standby = Arc::new(Standby::new());
 
     let command_handler = Arc::new(command_handler::CommandHandler {